@@ -1,8 +1,38 @@
+use std::rc::Rc;
+
 use crate::token::Token;
 use crate::literal::Literal;
 use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
 use crate::error::Error;
+use crate::rpn_printer::RpnPrinter;
+use crate::tree_printer::TreePrinter;
+use crate::dot_printer::DotPrinter;
+use crate::json_printer::JsonPrinter;
+use crate::unparser::Unparser;
+
+/// Renders a whole program. Every AST output format implements this, so they
+/// can be swapped for one another.
+pub trait Printer {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error>;
+}
+
+/// The names `printer` accepts, in the order they are listed to users.
+pub const FORMATS: [&str; 6] = ["sexpr", "rpn", "tree", "dot", "json", "lox"];
 
+pub fn printer(format: &str) -> Option<Box<dyn Printer>> {
+    match format {
+        "sexpr" => Some(Box::new(AstPrinter)),
+        "rpn" => Some(Box::new(RpnPrinter)),
+        "tree" => Some(Box::new(TreePrinter)),
+        "dot" => Some(Box::new(DotPrinter::new())),
+        "json" => Some(Box::new(JsonPrinter)),
+        "lox" => Some(Box::new(Unparser::new())),
+        _ => None,
+    }
+}
+
+/// Prints Lisp-style s-expressions such as `(* (- 123) (group 45.67))`.
 pub struct AstPrinter;
 
 impl AstPrinter {
@@ -10,6 +40,15 @@ impl AstPrinter {
         expr.accept(self)
     }
 
+    fn print_statements(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = String::new();
+        for statement in statements {
+            result.push(' ');
+            result.push_str(&statement.accept(self)?);
+        }
+        Ok(result)
+    }
+
     fn parenthesize(&mut self, name: String, expressions: Vec<&Expr>) -> Result<String, Error> {
         let mut result = String::new();
         result.push('(');
@@ -24,28 +63,132 @@ impl AstPrinter {
 }
 
 impl Visitor<String> for AstPrinter {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        self.parenthesize(format!("= {}", name.lexeme), vec![value])
+    }
+
     fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
         self.parenthesize(operator.lexeme.clone(), vec![left, right])
     }
 
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        let mut expressions: Vec<&Expr> = vec![callee];
+        expressions.extend(arguments);
+        self.parenthesize(String::from("call"), expressions)
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<String, Error> {
+        self.parenthesize(format!(". {}", name.lexeme), vec![object])
+    }
+
     fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
         self.parenthesize(String::from("group"), vec![expression])
     }
 
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let mut result: String = format!("(interpolate {:?}", segments[0]);
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            result.push_str(&format!(" {} {:?}", expression.accept(self)?, segment));
+        }
+        result.push(')');
+        Ok(result)
+    }
+
     fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
         Ok(literal.to_string())
     }
 
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        self.parenthesize(operator.lexeme.clone(), vec![left, right])
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        self.parenthesize(format!("= . {}", name.lexeme), vec![object, value])
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(format!("(super {})", method.lexeme))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<String, Error> {
+        Ok(String::from("this"))
+    }
+
     fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
         self.parenthesize(operator.lexeme.clone(), vec![right])
     }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(name.lexeme.clone())
+    }
+}
+
+impl StmtVisitor<String> for AstPrinter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(format!("(block{})", self.print_statements(statements)?))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        let superclass: String = match superclass {
+            Some(superclass) => format!(" < {}", superclass.accept(self)?),
+            None => String::new(),
+        };
+        Ok(format!("(class {}{}{})", name.lexeme, superclass, self.print_statements(methods)?))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        self.parenthesize(String::from(";"), vec![expression])
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        Ok(format!("(fun {} ({}){})", name.lexeme, params.join(" "), self.print_statements(body)?))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let else_branch: String = match else_branch {
+            Some(else_branch) => format!(" {}", else_branch.accept(self)?),
+            None => String::new(),
+        };
+        Ok(format!("(if {} {}{})", condition.accept(self)?, then_branch.accept(self)?, else_branch))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        self.parenthesize(String::from("print"), vec![expression])
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        self.parenthesize(String::from("return"), value.into_iter().collect())
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        self.parenthesize(format!("var {}", name.lexeme), initializer.into_iter().collect())
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        Ok(format!("(while {} {})", condition.accept(self)?, body.accept(self)?))
+    }
+}
+
+impl Printer for AstPrinter {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = String::new();
+        for statement in statements {
+            result.push_str(&statement.accept(self)?);
+            result.push('\n');
+        }
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::token::Token;
     use crate::token_type::TokenType;
-    use crate::ast_printer::AstPrinter;
+    use crate::ast_printer::{AstPrinter, Printer};
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::stmt::Stmt;
     use crate::literal::Literal;
     use crate::expr::Expr;
     
@@ -61,4 +204,16 @@ mod tests {
 
         assert_eq!(printer.print(expression).unwrap(), "(* (- 123) (group 45.67))".to_string());
     }
+
+    #[test]
+    fn test_statements() {
+        let source: &str = "class B < A { m(x) { return x; } } var a = 1; while (a) { if (a) print a; else a = nil; }";
+        let statements: Vec<Stmt> = Parser::new(Scanner::new(String::from(source)).scan_tokens()).parse();
+
+        assert_eq!(AstPrinter.print_program(&statements).unwrap(), concat!(
+            "(class B < A (fun m (x) (return x)))\n",
+            "(var a 1)\n",
+            "(while a (block (if a (print a) (; (= a null)))))\n",
+        ));
+    }
 }
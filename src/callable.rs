@@ -0,0 +1,86 @@
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::interpreter::Interpreter;
+use crate::value::Value;
+use crate::error::Error;
+
+/// Anything that can appear on the left of a call expression.
+pub trait LoxCallable: std::fmt::Debug + std::fmt::Display {
+    fn arity(&self) -> usize;
+    fn call(self: Rc<Self>, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error>;
+}
+
+/// Native `clock()`: seconds since the Unix epoch.
+#[derive(Debug)]
+pub struct Clock;
+
+impl LoxCallable for Clock {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn call(self: Rc<Self>, _interpreter: &mut Interpreter, _arguments: Vec<Value>) -> Result<Value, Error> {
+        let now: f64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs_f64())
+            .unwrap_or(0.0);
+
+        Ok(Value::Number(now))
+    }
+}
+
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn>")
+    }
+}
+
+/// Native `argc()`: how many arguments followed the script on the command line.
+#[derive(Debug)]
+pub struct Argc(pub Rc<Vec<String>>);
+
+impl LoxCallable for Argc {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn call(self: Rc<Self>, _interpreter: &mut Interpreter, _arguments: Vec<Value>) -> Result<Value, Error> {
+        Ok(Value::Number(self.0.len() as f64))
+    }
+}
+
+impl std::fmt::Display for Argc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn>")
+    }
+}
+
+/// Native `arg(index)`: the command-line argument at `index`, or `nil` when
+/// there is no such argument.
+#[derive(Debug)]
+pub struct Arg(pub Rc<Vec<String>>);
+
+impl LoxCallable for Arg {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn call(self: Rc<Self>, _interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
+        let argument: Option<&String> = match arguments[0] {
+            Value::Number(index) if index >= 0.0 && index.fract() == 0.0 => self.0.get(index as usize),
+            _ => None,
+        };
+
+        match argument {
+            Some(argument) => Ok(Value::String(argument.clone())),
+            None => Ok(Value::Nil),
+        }
+    }
+}
+
+impl std::fmt::Display for Arg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<native fn>")
+    }
+}
@@ -0,0 +1,225 @@
+use crate::token::Token;
+use crate::token_type::TokenType;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// Stable identifiers for every diagnostic the front end and runtime can raise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    // Scanner
+    UnexpectedCharacter,
+    UnterminatedString,
+    InvalidNumber,
+    InvalidEscape,
+    UnterminatedComment,
+
+    // Parser
+    ExpectedExpression,
+    ExpectedToken,
+    InvalidAssignmentTarget,
+    TooManyArguments,
+    TooDeeplyNested,
+
+    // Resolver
+    OwnInitializer,
+    AlreadyDeclared,
+    TopLevelReturn,
+    InitializerReturn,
+    ThisOutsideClass,
+    InvalidSuper,
+    InheritsFromSelf,
+
+    // Interpreter
+    Runtime,
+
+    // A bug in rlox itself
+    Internal,
+}
+
+impl Code {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Code::UnexpectedCharacter => "E0001",
+            Code::UnterminatedString => "E0002",
+            Code::InvalidNumber => "E0003",
+            Code::InvalidEscape => "E0004",
+            Code::UnterminatedComment => "E0005",
+            Code::ExpectedExpression => "E0100",
+            Code::ExpectedToken => "E0101",
+            Code::InvalidAssignmentTarget => "E0102",
+            Code::TooManyArguments => "E0103",
+            Code::TooDeeplyNested => "E0104",
+            Code::OwnInitializer => "E0200",
+            Code::AlreadyDeclared => "E0201",
+            Code::TopLevelReturn => "E0202",
+            Code::InitializerReturn => "E0203",
+            Code::ThisOutsideClass => "E0204",
+            Code::InvalidSuper => "E0205",
+            Code::InheritsFromSelf => "E0206",
+            Code::Runtime => "E0300",
+            Code::Internal => "E0900",
+        }
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A region of source text. `column` is 1-based; 0 means the column is unknown.
+/// `start..end` is a byte range into the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, start: usize, end: usize) -> Self {
+        Self {
+            line,
+            column,
+            start,
+            end,
+        }
+    }
+}
+
+impl From<&Token> for Span {
+    fn from(token: &Token) -> Self {
+        Span::new(token.line, token.column, token.start, token.end)
+    }
+}
+
+/// A secondary annotation on a diagnostic, such as "variable declared here".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Code,
+    pub message: String,
+    pub span: Span,
+    /// Where in the line the problem is, e.g. `at 'foo'` or `at end`.
+    pub location: Option<String>,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: Code, message: String, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            code,
+            message,
+            span,
+            location: None,
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// Builds an error pointing at `token`, located the way the parser reports it.
+    pub fn at_token(code: Code, token: &Token, message: String) -> Self {
+        let location: String = match token.token_type {
+            TokenType::Eof => String::from("at end"),
+            _ => format!("at '{}'", token.lexeme),
+        };
+
+        Diagnostic::error(code, message, Span::from(token)).with_location(location)
+    }
+
+    pub fn with_location(mut self, location: String) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn with_label(mut self, span: Span, message: String) -> Self {
+        self.labels.push(Label { span, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[line {}] {}[{}]", self.span.line, self.severity, self.code)?;
+        if let Some(location) = &self.location {
+            write!(f, " {}", location)?;
+        }
+        write!(f, ": {}", self.message)?;
+
+        for note in &self.notes {
+            write!(f, "\n  = note: {}", note)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Lox;
+    use crate::diagnostic::{Code, Severity};
+
+    #[test]
+    fn test_diagnostics_are_collected() {
+        let mut lox: Lox = Lox::new();
+        lox.run(String::from("@ # print 1;")).unwrap();
+
+        let codes: Vec<Code> = lox.diagnostics().iter().map(|diagnostic| diagnostic.code).collect();
+        assert_eq!(codes, vec![Code::UnexpectedCharacter, Code::UnexpectedCharacter]);
+        assert!(lox.diagnostics().iter().all(|diagnostic| diagnostic.severity == Severity::Error));
+        assert_eq!(lox.diagnostics()[1].span.start, 2);
+    }
+
+    #[test]
+    fn test_runtime_error_becomes_diagnostic() {
+        let mut lox: Lox = Lox::new();
+        lox.run(String::from("var a = 1;\n-\"a\";")).unwrap();
+
+        let diagnostic = &lox.diagnostics()[0];
+        assert_eq!(diagnostic.code, Code::Runtime);
+        assert_eq!(diagnostic.span.line, 2);
+        assert_eq!(diagnostic.message, "Operand must be a number.");
+    }
+
+    #[test]
+    fn test_display_includes_location_and_code() {
+        let mut lox: Lox = Lox::new();
+        lox.run(String::from("print 1")).unwrap();
+
+        assert_eq!(lox.diagnostics()[0].to_string(), "[line 1] error[E0101] at end: Expect ';' after value.");
+    }
+}
@@ -0,0 +1,245 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::error::Error;
+use crate::ast_printer::Printer;
+
+/// Prints a Graphviz `digraph` with a box per node and an edge to each child,
+/// ready for `dot -Tsvg`. Visiting a node returns its DOT identifier.
+pub struct DotPrinter {
+    lines: Vec<String>,
+    next_id: usize,
+}
+
+impl Default for DotPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DotPrinter {
+    pub fn new() -> Self {
+        Self {
+            lines: vec![],
+            next_id: 0,
+        }
+    }
+
+    pub fn print(&mut self, expr: &Expr) -> Result<String, Error> {
+        self.reset();
+        expr.accept(self)?;
+        Ok(self.graph())
+    }
+
+    fn reset(&mut self) {
+        self.lines.clear();
+        self.next_id = 0;
+    }
+
+    fn graph(&mut self) -> String {
+        let mut result: String = String::from("digraph ast {\n    ordering=out;\n    node [shape=box, fontname=\"monospace\"];\n");
+        for line in self.lines.drain(..) {
+            result.push_str("    ");
+            result.push_str(&line);
+            result.push('\n');
+        }
+        result.push_str("}\n");
+        result
+    }
+
+    /// Adds a node labelled `label` with edges to `children`, which are
+    /// already-added nodes, and returns the new node's identifier.
+    fn node(&mut self, label: &str, children: Vec<String>) -> String {
+        let id: String = format!("n{}", self.next_id);
+        self.next_id += 1;
+
+        self.lines.push(format!("{} [label=\"{}\"];", id, escape(label)));
+        for child in children {
+            self.lines.push(format!("{} -> {};", id, child));
+        }
+        id
+    }
+
+    fn expressions(&mut self, expressions: Vec<&Expr>) -> Result<Vec<String>, Error> {
+        expressions.into_iter().map(|expr| expr.accept(self)).collect()
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) -> Result<Vec<String>, Error> {
+        statements.iter().map(|statement| statement.accept(self)).collect()
+    }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+impl Visitor<String> for DotPrinter {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![value])?;
+        Ok(self.node(&format!("Assign {}", name.lexeme), children))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![left, right])?;
+        Ok(self.node(&format!("Binary {}", operator.lexeme), children))
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        let mut expressions: Vec<&Expr> = vec![callee];
+        expressions.extend(arguments);
+        let children: Vec<String> = self.expressions(expressions)?;
+        Ok(self.node("Call", children))
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![object])?;
+        Ok(self.node(&format!("Get .{}", name.lexeme), children))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![expression])?;
+        Ok(self.node("Grouping", children))
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let mut children: Vec<String> = vec![self.node(&format!("Literal {:?}", segments[0]), vec![])];
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            children.push(expression.accept(self)?);
+            children.push(self.node(&format!("Literal {:?}", segment), vec![]));
+        }
+        Ok(self.node("Interpolation", children))
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
+        let label: String = match literal {
+            Literal::String(value) => format!("Literal {:?}", value),
+            Literal::Empty => String::from("Literal nil"),
+            _ => format!("Literal {}", literal),
+        };
+        Ok(self.node(&label, vec![]))
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![left, right])?;
+        Ok(self.node(&format!("Logical {}", operator.lexeme), children))
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![object, value])?;
+        Ok(self.node(&format!("Set .{}", name.lexeme), children))
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(self.node(&format!("Super .{}", method.lexeme), vec![]))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<String, Error> {
+        Ok(self.node("This", vec![]))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![right])?;
+        Ok(self.node(&format!("Unary {}", operator.lexeme), children))
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(self.node(&format!("Variable {}", name.lexeme), vec![]))
+    }
+}
+
+impl StmtVisitor<String> for DotPrinter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let children: Vec<String> = self.statements(statements)?;
+        Ok(self.node("Block", children))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        let label: String = match superclass {
+            Some(Expr::Variable { name: superclass, .. }) => format!("Class {} < {}", name.lexeme, superclass.lexeme),
+            _ => format!("Class {}", name.lexeme),
+        };
+        let children: Vec<String> = self.statements(methods)?;
+        Ok(self.node(&label, children))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![expression])?;
+        Ok(self.node("Expression", children))
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        let children: Vec<String> = self.statements(body)?;
+        Ok(self.node(&format!("Function {}({})", name.lexeme, params.join(", ")), children))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let mut children: Vec<String> = vec![condition.accept(self)?, then_branch.accept(self)?];
+        if let Some(else_branch) = else_branch {
+            children.push(else_branch.accept(self)?);
+        }
+        Ok(self.node("If", children))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(vec![expression])?;
+        Ok(self.node("Print", children))
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(value.into_iter().collect())?;
+        Ok(self.node("Return", children))
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        let children: Vec<String> = self.expressions(initializer.into_iter().collect())?;
+        Ok(self.node(&format!("Var {}", name.lexeme), children))
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        let children: Vec<String> = vec![condition.accept(self)?, body.accept(self)?];
+        Ok(self.node("While", children))
+    }
+}
+
+impl Printer for DotPrinter {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        self.reset();
+        let children: Vec<String> = self.statements(statements)?;
+        self.node("Program", children);
+        Ok(self.graph())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::stmt::Stmt;
+    use crate::ast_printer::Printer;
+    use crate::dot_printer::DotPrinter;
+
+    #[test]
+    fn test_nodes_and_edges() {
+        let statements: Vec<Stmt> = Parser::new(Scanner::new(String::from("print \"a\" + b;")).scan_tokens()).parse();
+
+        assert_eq!(DotPrinter::new().print_program(&statements).unwrap(), concat!(
+            "digraph ast {\n",
+            "    ordering=out;\n",
+            "    node [shape=box, fontname=\"monospace\"];\n",
+            "    n0 [label=\"Literal \\\"a\\\"\"];\n",
+            "    n1 [label=\"Variable b\"];\n",
+            "    n2 [label=\"Binary +\"];\n",
+            "    n2 -> n0;\n",
+            "    n2 -> n1;\n",
+            "    n3 [label=\"Print\"];\n",
+            "    n3 -> n2;\n",
+            "    n4 [label=\"Program\"];\n",
+            "    n4 -> n3;\n",
+            "}\n",
+        ));
+    }
+}
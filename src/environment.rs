@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::token::Token;
+use crate::value::Value;
+use crate::error::Error;
+
+#[derive(Debug, Default)]
+pub struct Environment {
+    enclosing: Option<Rc<RefCell<Environment>>>,
+    values: HashMap<String, Value>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self {
+            enclosing: None,
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn from(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            enclosing: Some(enclosing),
+            values: HashMap::new(),
+        }
+    }
+
+    pub fn define(&mut self, name: String, value: Value) {
+        self.values.insert(name, value);
+    }
+
+    /// The variables defined directly in this scope, sorted by name.
+    pub fn bindings(&self) -> Vec<(String, Value)> {
+        let mut bindings: Vec<(String, Value)> = self.values.iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        bindings.sort_by(|(left, _), (right, _)| left.cmp(right));
+        bindings
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, Error> {
+        if let Some(value) = self.values.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(Error::runtime(name, &format!("Undefined variable '{}'.", name.lexeme))),
+        }
+    }
+
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Value, Error> {
+        if distance > 0 { return self.ancestor(distance).borrow().get_at(0, name); }
+
+        match self.values.get(&name.lexeme) {
+            Some(value) => Ok(value.clone()),
+            None => Err(Error::runtime(name, &format!("Undefined variable '{}'.", name.lexeme))),
+        }
+    }
+
+    pub fn assign_at(&mut self, distance: usize, name: &Token, value: Value) -> Result<(), Error> {
+        if distance > 0 { return self.ancestor(distance).borrow_mut().assign_at(0, name, value); }
+
+        self.values.insert(name.lexeme.clone(), value);
+        Ok(())
+    }
+
+    fn ancestor(&self, distance: usize) -> Rc<RefCell<Environment>> {
+        let mut environment: Rc<RefCell<Environment>> = Rc::clone(self.enclosing.as_ref().expect("Resolved scope depth exceeds environment chain"));
+
+        for _ in 1..distance {
+            let enclosing: Rc<RefCell<Environment>> = Rc::clone(environment.borrow().enclosing.as_ref().expect("Resolved scope depth exceeds environment chain"));
+            environment = enclosing;
+        }
+
+        environment
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), Error> {
+        if let Some(slot) = self.values.get_mut(&name.lexeme) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(Error::runtime(name, &format!("Undefined variable '{}'.", name.lexeme))),
+        }
+    }
+}
@@ -1,7 +1,30 @@
+use crate::token::Token;
+use crate::value::Value;
+
 #[derive(Debug)]
 pub enum Error {
     Io(std::io::Error),
     Parser,
-    Runtime,
+    Runtime {
+        /// Boxed so that every `Result<_, Error>` stays small.
+        token: Box<Token>,
+        message: String,
+    },
+    /// Not a failure: unwinds the interpreter out of a function body on `return`.
+    Return(Value),
+}
+
+impl Error {
+    pub fn runtime(token: &Token, message: &str) -> Self {
+        Error::Runtime {
+            token: Box::new(token.clone()),
+            message: String::from(message),
+        }
+    }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
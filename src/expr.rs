@@ -1,39 +1,133 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use crate::token::Token;
 use crate::literal::Literal;
 use crate::error::Error;
 
+#[derive(Debug)]
 pub enum Expr {
+    Assign {
+        id: usize,
+        name: Token,
+        value: Box<Expr>,
+    },
     Binary {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    Call {
+        callee: Box<Expr>,
+        paren: Token,
+        arguments: Vec<Expr>,
+    },
+    Get {
+        object: Box<Expr>,
+        name: Token,
+    },
     Grouping {
         expression: Box<Expr>
     },
+    /// A string with embedded expressions, `"a ${x} b"`. There is always one
+    /// more literal segment than expression.
+    Interpolation {
+        segments: Vec<String>,
+        expressions: Vec<Expr>,
+    },
     Literal {
         literal: Literal,
     },
+    Logical {
+        left: Box<Expr>,
+        operator: Token,
+        right: Box<Expr>,
+    },
+    Set {
+        object: Box<Expr>,
+        name: Token,
+        value: Box<Expr>,
+    },
+    Super {
+        id: usize,
+        keyword: Token,
+        method: Token,
+    },
+    This {
+        id: usize,
+        keyword: Token,
+    },
     Unary {
         operator: Token,
         right: Box<Expr>,
     },
+    Variable {
+        id: usize,
+        name: Token,
+    },
+}
+
+/// Structural equality: the same shape, operators, names and literal values.
+/// Resolver ids and source positions are ignored.
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Expr::Assign { name: a, value: x, .. }, Expr::Assign { name: b, value: y, .. }) => a.equivalent(b) && x == y,
+            (Expr::Binary { left: l1, operator: a, right: r1 }, Expr::Binary { left: l2, operator: b, right: r2 }) => a.equivalent(b) && l1 == l2 && r1 == r2,
+            (Expr::Call { callee: c1, paren: a, arguments: x }, Expr::Call { callee: c2, paren: b, arguments: y }) => a.equivalent(b) && c1 == c2 && x == y,
+            (Expr::Get { object: o1, name: a }, Expr::Get { object: o2, name: b }) => a.equivalent(b) && o1 == o2,
+            (Expr::Grouping { expression: x }, Expr::Grouping { expression: y }) => x == y,
+            (Expr::Interpolation { segments: a, expressions: x }, Expr::Interpolation { segments: b, expressions: y }) => a == b && x == y,
+            (Expr::Literal { literal: x }, Expr::Literal { literal: y }) => x == y,
+            (Expr::Logical { left: l1, operator: a, right: r1 }, Expr::Logical { left: l2, operator: b, right: r2 }) => a.equivalent(b) && l1 == l2 && r1 == r2,
+            (Expr::Set { object: o1, name: a, value: x }, Expr::Set { object: o2, name: b, value: y }) => a.equivalent(b) && o1 == o2 && x == y,
+            (Expr::Super { method: a, .. }, Expr::Super { method: b, .. }) => a.equivalent(b),
+            (Expr::This { .. }, Expr::This { .. }) => true,
+            (Expr::Unary { operator: a, right: x }, Expr::Unary { operator: b, right: y }) => a.equivalent(b) && x == y,
+            (Expr::Variable { name: a, .. }, Expr::Variable { name: b, .. }) => a.equivalent(b),
+            _ => false,
+        }
+    }
 }
 
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
 impl Expr {
+    /// Hands out the identity the resolver uses to key variable references.
+    pub fn next_id() -> usize {
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    }
+
     pub fn accept<R>(&self, visitor: &mut dyn Visitor<R>) -> Result<R, Error> {
         match self {
+            Expr::Assign { id, name, value } => visitor.visit_assign_expr( *id, name, value ),
             Expr::Binary { left, operator, right } => visitor.visit_binary_expr( left, operator, right ),
+            Expr::Call { callee, paren, arguments } => visitor.visit_call_expr( callee, paren, arguments ),
+            Expr::Get { object, name } => visitor.visit_get_expr( object, name ),
             Expr::Grouping { expression } => visitor.visit_grouping_expr( expression ),
+            Expr::Interpolation { segments, expressions } => visitor.visit_interpolation_expr( segments, expressions ),
             Expr::Literal { literal } => visitor.visit_literal_expr( literal ),
+            Expr::Logical { left, operator, right } => visitor.visit_logical_expr( left, operator, right ),
+            Expr::Set { object, name, value } => visitor.visit_set_expr( object, name, value ),
+            Expr::Super { id, keyword, method } => visitor.visit_super_expr( *id, keyword, method ),
+            Expr::This { id, keyword } => visitor.visit_this_expr( *id, keyword ),
             Expr::Unary { operator, right } => visitor.visit_unary_expr( operator, right ),
+            Expr::Variable { id, name } => visitor.visit_variable_expr( *id, name ),
         }
     }
 }
 
 pub trait Visitor<R> {
+    fn visit_assign_expr(&mut self, id: usize, name: &Token, value: &Expr) -> Result<R, Error>;
     fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<R, Error>;
+    fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> Result<R, Error>;
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<R, Error>;
     fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<R, Error>;
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<R, Error>;
     fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<R, Error>;
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<R, Error>;
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<R, Error>;
+    fn visit_super_expr(&mut self, id: usize, keyword: &Token, method: &Token) -> Result<R, Error>;
+    fn visit_this_expr(&mut self, id: usize, keyword: &Token) -> Result<R, Error>;
     fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<R, Error>;
+    fn visit_variable_expr(&mut self, id: usize, name: &Token) -> Result<R, Error>;
 }
@@ -0,0 +1,677 @@
+use std::rc::Rc;
+
+use crate::token::{Token, Trivia, TriviaKind};
+use crate::token_type::TokenType;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::scanner::Scanner;
+use crate::parser::Parser;
+use crate::error::Error;
+use crate::diagnostic::{Code, Diagnostic};
+
+const INDENT: &str = "    ";
+
+/// Formats Lox source into its canonical layout, keeping every comment.
+/// Returns the scanner and parser diagnostics if the source doesn't parse, or
+/// an internal error if the formatter loses its place in it.
+pub fn format(source: &str) -> Result<String, Vec<Diagnostic>> {
+    let mut scanner: Scanner = Scanner::new(String::from(source)).with_trivia(true);
+    let tokens: Vec<Token> = scanner.scan_tokens();
+    let mut diagnostics: Vec<Diagnostic> = scanner.take_diagnostics();
+
+    let mut parser: Parser = Parser::new(tokens.clone());
+    let statements: Vec<Stmt> = parser.parse();
+    diagnostics.extend(parser.take_diagnostics());
+
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) { return Err(diagnostics); }
+
+    let mut formatter: Formatter = Formatter::new(tokens);
+    for statement in &statements {
+        formatter.statement(statement);
+    }
+    formatter.finish()
+}
+
+/// A pretty-printer that walks the AST and the lossless token stream in step.
+///
+/// The AST decides the layout. Each lexeme is copied from its source token, so
+/// number spellings and string escapes survive, and the comments in the
+/// token's trivia are re-emitted next to it. The token stream also tells
+/// desugared `for` loops apart from hand-written `while` loops.
+struct Formatter {
+    tokens: Vec<Token>,
+    current: usize,
+    out: String,
+    /// The line being built, without its indentation.
+    line: String,
+    line_indent: usize,
+    indent: usize,
+    /// Set when a comment forces a statement onto more than one line.
+    continuation: bool,
+    space: bool,
+    blank_line: bool,
+    /// The first token that didn't match what the AST called for. Nothing is
+    /// written after it, and [`Formatter::finish`] reports it.
+    out_of_step: Option<Token>,
+}
+
+impl Formatter {
+    fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            current: 0,
+            out: String::new(),
+            line: String::new(),
+            line_indent: 0,
+            indent: 0,
+            continuation: false,
+            space: false,
+            blank_line: false,
+            out_of_step: None,
+        }
+    }
+
+    /// The formatted source, unless the walk fell out of step with the token
+    /// stream or stopped short of its end.
+    fn finish(mut self) -> Result<String, Vec<Diagnostic>> {
+        if self.out_of_step.is_none() && !self.check(TokenType::Eof) {
+            self.out_of_step = Some(self.tokens[self.current].clone());
+        }
+        if let Some(token) = &self.out_of_step {
+            let message: String = String::from("The formatter lost its place in the source here, so nothing was written. This is a bug in rlox.");
+            return Err(vec![Diagnostic::at_token(Code::Internal, token, message)]);
+        }
+
+        self.flush_leading();
+        self.end_line();
+        Ok(self.out)
+    }
+
+    fn statement(&mut self, statement: &Stmt) {
+        let _ = statement.accept(self);
+        self.end_line();
+    }
+
+    fn expression(&mut self, expression: &Expr) {
+        let _ = expression.accept(self);
+    }
+
+    /// Writes the body of an `if`, `while` or `for`: braces stay on the header's line.
+    fn body(&mut self, body: &Stmt) {
+        self.space();
+        match body {
+            Stmt::Block { statements } if self.check(TokenType::LeftBrace) => {
+                self.token(TokenType::LeftBrace);
+                self.block(statements);
+            },
+            _ => { let _ = body.accept(self); },
+        }
+    }
+
+    /// Writes the statements after an already written `{`, and the closing `}`.
+    fn block(&mut self, statements: &[Stmt]) {
+        if statements.is_empty() && !self.has_comments() && !self.line.is_empty() {
+            self.token(TokenType::RightBrace);
+            return;
+        }
+
+        self.end_line();
+        self.indent += 1;
+        for statement in statements {
+            self.statement(statement);
+        }
+        // Comments before the `}` belong inside the block.
+        self.flush_leading();
+        self.indent -= 1;
+        self.token(TokenType::RightBrace);
+    }
+
+    fn function(&mut self, params: &[Token], body: &[Stmt]) {
+        self.token(TokenType::Identifier);
+        self.token(TokenType::LeftParen);
+        for (i, _) in params.iter().enumerate() {
+            if i > 0 {
+                self.token(TokenType::Comma);
+                self.space();
+            }
+            self.token(TokenType::Identifier);
+        }
+        self.token(TokenType::RightParen);
+        self.space();
+        self.token(TokenType::LeftBrace);
+        self.block(body);
+    }
+
+    /// Re-sugars the `Block`/`While` nest the parser builds for a `for` loop.
+    fn for_loop(&mut self, initializer: Option<&Stmt>, condition: &Expr, body: &Stmt) {
+        self.token(TokenType::For);
+        self.space();
+        self.token(TokenType::LeftParen);
+
+        match initializer {
+            Some(initializer) => { let _ = initializer.accept(self); },
+            None => self.token(TokenType::Semicolon),
+        }
+
+        if !self.check(TokenType::Semicolon) {
+            self.space();
+            self.expression(condition);
+        }
+        self.token(TokenType::Semicolon);
+
+        let body: &Stmt = match (self.check(TokenType::RightParen), body) {
+            (false, Stmt::Block { statements }) => {
+                if let [body, Stmt::Expression { expression: increment }] = &statements[..] {
+                    self.space();
+                    self.expression(increment);
+                    body
+                } else {
+                    body
+                }
+            },
+            _ => body,
+        };
+        self.token(TokenType::RightParen);
+
+        self.body(body);
+    }
+
+    /// Writes the next source token, along with the comments around it.
+    fn token(&mut self, token_type: TokenType) {
+        if self.out_of_step.is_some() { return; }
+
+        let token: Token = self.tokens[self.current].clone();
+        if token.token_type != token_type {
+            self.out_of_step = Some(token);
+            return;
+        }
+        self.current += 1;
+
+        self.leading(&token.leading_trivia, token_type != TokenType::RightBrace);
+        // Punctuation hugs what comes before it, even a block comment.
+        if matches!(token_type, TokenType::RightParen | TokenType::Semicolon | TokenType::Comma | TokenType::Dot) {
+            self.space = false;
+        }
+        self.word(&token.lexeme);
+        self.trailing(&token.trailing_trivia);
+    }
+
+    /// Writes the next source token whatever its type, for literals.
+    fn literal(&mut self) {
+        let token_type: TokenType = self.tokens[self.current].token_type.clone();
+        self.token(token_type);
+    }
+
+    fn check(&self, token_type: TokenType) -> bool {
+        self.tokens[self.current].token_type == token_type
+    }
+
+    fn has_comments(&self) -> bool {
+        self.tokens[self.current].leading_trivia.iter()
+            .any(|trivia| matches!(trivia.kind, TriviaKind::LineComment | TriviaKind::BlockComment))
+    }
+
+    /// Writes the comments before the next token now, at the current indentation.
+    fn flush_leading(&mut self) {
+        let trivia: Vec<Trivia> = std::mem::take(&mut self.tokens[self.current].leading_trivia);
+        self.leading(&trivia, false);
+    }
+
+    /// Comments that started their own line keep it; others stay inline. A run
+    /// of blank lines before a statement or comment collapses to one.
+    fn leading(&mut self, trivia: &[Trivia], keep_blank_line: bool) {
+        let mut newlines: usize = 0;
+        // Whether the statement had already begun before this comment's line.
+        let mut mid_statement: bool = false;
+        // Set while an own-line block comment may still share its line with what follows.
+        let mut open_line: bool = false;
+
+        for piece in trivia {
+            match piece.kind {
+                TriviaKind::Newline => {
+                    newlines += 1;
+                    if open_line {
+                        self.end_line();
+                        self.continuation = mid_statement;
+                        open_line = false;
+                    }
+                },
+                TriviaKind::LineComment | TriviaKind::BlockComment => {
+                    if open_line {
+                        self.comment(piece);
+                    } else if newlines > 0 || (self.out.is_empty() && self.line.is_empty()) {
+                        mid_statement = !self.line.is_empty() || self.continuation;
+                        if !self.line.is_empty() {
+                            self.end_line();
+                        } else if newlines > 1 {
+                            self.blank_line = true;
+                        }
+
+                        self.continuation = mid_statement;
+                        self.word(&piece.text);
+                        match piece.kind {
+                            TriviaKind::LineComment => {
+                                self.end_line();
+                                self.continuation = mid_statement;
+                            },
+                            _ => {
+                                self.space();
+                                open_line = true;
+                            },
+                        }
+                    } else {
+                        self.comment(piece);
+                    }
+                    newlines = 0;
+                },
+                TriviaKind::Whitespace | TriviaKind::Skipped => (),
+            }
+        }
+
+        if newlines > 1 && keep_blank_line && self.line.is_empty() {
+            self.blank_line = true;
+        }
+    }
+
+    fn trailing(&mut self, trivia: &[Trivia]) {
+        for piece in trivia {
+            if matches!(piece.kind, TriviaKind::LineComment | TriviaKind::BlockComment) {
+                self.comment(piece);
+            }
+        }
+    }
+
+    fn comment(&mut self, comment: &Trivia) {
+        self.space();
+        self.word(&comment.text);
+        match comment.kind {
+            // Nothing else can follow a line comment on its line.
+            TriviaKind::LineComment => self.break_line(),
+            _ => self.space(),
+        }
+    }
+
+    fn space(&mut self) {
+        self.space = true;
+    }
+
+    fn word(&mut self, text: &str) {
+        if self.line.is_empty() {
+            self.line_indent = self.indent + self.continuation as usize;
+        } else if self.space {
+            self.line.push(' ');
+        }
+        self.space = false;
+        self.line.push_str(text);
+    }
+
+    /// Ends a line in the middle of a statement; the rest of it is indented once more.
+    fn break_line(&mut self) {
+        self.end_line();
+        self.continuation = true;
+    }
+
+    fn end_line(&mut self) {
+        self.continuation = false;
+        self.space = false;
+        if self.line.is_empty() { return; }
+
+        if self.blank_line && !self.out.is_empty() && !self.out.ends_with("{\n") {
+            self.out.push('\n');
+        }
+        self.blank_line = false;
+
+        for _ in 0..self.line_indent {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(self.line.trim_end());
+        self.out.push('\n');
+        self.line.clear();
+    }
+}
+
+impl Visitor<()> for Formatter {
+    fn visit_assign_expr(&mut self, _id: usize, _name: &Token, value: &Expr) -> Result<(), Error> {
+        self.token(TokenType::Identifier);
+        self.space();
+        self.token(TokenType::Equal);
+        self.space();
+        value.accept(self)
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<(), Error> {
+        left.accept(self)?;
+        self.space();
+        self.token(operator.token_type.clone());
+        self.space();
+        right.accept(self)
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<(), Error> {
+        callee.accept(self)?;
+        self.token(TokenType::LeftParen);
+        for (i, argument) in arguments.iter().enumerate() {
+            if i > 0 {
+                self.token(TokenType::Comma);
+                self.space();
+            }
+            argument.accept(self)?;
+        }
+        self.token(TokenType::RightParen);
+        Ok(())
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, _name: &Token) -> Result<(), Error> {
+        object.accept(self)?;
+        self.token(TokenType::Dot);
+        self.token(TokenType::Identifier);
+        Ok(())
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<(), Error> {
+        self.token(TokenType::LeftParen);
+        expression.accept(self)?;
+        self.token(TokenType::RightParen);
+        Ok(())
+    }
+
+    fn visit_interpolation_expr(&mut self, _segments: &[String], expressions: &[Expr]) -> Result<(), Error> {
+        for expression in expressions {
+            self.literal();
+            self.expression(expression);
+        }
+        self.literal();
+        Ok(())
+    }
+
+    fn visit_literal_expr(&mut self, _literal: &Literal ) -> Result<(), Error> {
+        self.literal();
+        Ok(())
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<(), Error> {
+        left.accept(self)?;
+        self.space();
+        self.token(operator.token_type.clone());
+        self.space();
+        right.accept(self)
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, _name: &Token, value: &Expr) -> Result<(), Error> {
+        object.accept(self)?;
+        self.token(TokenType::Dot);
+        self.token(TokenType::Identifier);
+        self.space();
+        self.token(TokenType::Equal);
+        self.space();
+        value.accept(self)
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, _method: &Token) -> Result<(), Error> {
+        self.token(TokenType::Super);
+        self.token(TokenType::Dot);
+        self.token(TokenType::Identifier);
+        Ok(())
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<(), Error> {
+        self.token(TokenType::This);
+        Ok(())
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<(), Error> {
+        self.token(operator.token_type.clone());
+        right.accept(self)
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, _name: &Token) -> Result<(), Error> {
+        self.token(TokenType::Identifier);
+        Ok(())
+    }
+}
+
+impl StmtVisitor<()> for Formatter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), Error> {
+        if self.check(TokenType::For) {
+            if let [initializer, Stmt::While { condition, body }] = statements {
+                self.for_loop(Some(initializer), condition, body);
+                return Ok(());
+            }
+        }
+
+        self.token(TokenType::LeftBrace);
+        self.block(statements);
+        Ok(())
+    }
+
+    fn visit_class_stmt(&mut self, _name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<(), Error> {
+        self.token(TokenType::Class);
+        self.space();
+        self.token(TokenType::Identifier);
+        if superclass.is_some() {
+            self.space();
+            self.token(TokenType::Less);
+            self.space();
+            self.token(TokenType::Identifier);
+        }
+        self.space();
+        self.token(TokenType::LeftBrace);
+
+        if methods.is_empty() && !self.has_comments() && !self.line.is_empty() {
+            self.token(TokenType::RightBrace);
+            return Ok(());
+        }
+
+        self.end_line();
+        self.indent += 1;
+        for method in methods {
+            if let Stmt::Function { params, body, .. } = method {
+                self.function(params, body);
+                self.end_line();
+            }
+        }
+        self.flush_leading();
+        self.indent -= 1;
+        self.token(TokenType::RightBrace);
+        Ok(())
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        expression.accept(self)?;
+        self.token(TokenType::Semicolon);
+        Ok(())
+    }
+
+    fn visit_function_stmt(&mut self, _name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<(), Error> {
+        self.token(TokenType::Fun);
+        self.space();
+        self.function(params, body);
+        Ok(())
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<(), Error> {
+        self.token(TokenType::If);
+        self.space();
+        self.token(TokenType::LeftParen);
+        condition.accept(self)?;
+        self.token(TokenType::RightParen);
+        self.body(then_branch);
+
+        if let Some(else_branch) = else_branch {
+            self.space();
+            self.token(TokenType::Else);
+            self.body(else_branch);
+        }
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        self.token(TokenType::Print);
+        self.space();
+        expression.accept(self)?;
+        self.token(TokenType::Semicolon);
+        Ok(())
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<(), Error> {
+        self.token(TokenType::Return);
+        if let Some(value) = value {
+            self.space();
+            value.accept(self)?;
+        }
+        self.token(TokenType::Semicolon);
+        Ok(())
+    }
+
+    fn visit_var_stmt(&mut self, _name: &Token, initializer: Option<&Expr>) -> Result<(), Error> {
+        self.token(TokenType::Var);
+        self.space();
+        self.token(TokenType::Identifier);
+        if let Some(initializer) = initializer {
+            self.space();
+            self.token(TokenType::Equal);
+            self.space();
+            initializer.accept(self)?;
+        }
+        self.token(TokenType::Semicolon);
+        Ok(())
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<(), Error> {
+        if self.check(TokenType::For) {
+            self.for_loop(None, condition, body);
+            return Ok(());
+        }
+
+        self.token(TokenType::While);
+        self.space();
+        self.token(TokenType::LeftParen);
+        condition.accept(self)?;
+        self.token(TokenType::RightParen);
+        self.body(body);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::Token;
+    use crate::stmt::Stmt;
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::formatter::{Formatter, format};
+    use crate::diagnostic::Code;
+
+    fn formatted(source: &str) -> String {
+        let output: String = format(source).expect("source should parse");
+        assert_eq!(format(&output).unwrap(), output, "formatting is not idempotent");
+        output
+    }
+
+    #[test]
+    fn test_canonical_layout() {
+        let source: &str = concat!(
+            "fun add(a,b){return a+b;}\n",
+            "class Point<Base{init(x){this.x=-x;}}\n",
+            "var p=Point(1);if(p.x>=0 and !nil)print p.x;else{print \"neg\";}\n",
+            "while(false){}\n",
+        );
+
+        assert_eq!(formatted(source), concat!(
+            "fun add(a, b) {\n",
+            "    return a + b;\n",
+            "}\n",
+            "class Point < Base {\n",
+            "    init(x) {\n",
+            "        this.x = -x;\n",
+            "    }\n",
+            "}\n",
+            "var p = Point(1);\n",
+            "if (p.x >= 0 and !nil) print p.x; else {\n",
+            "    print \"neg\";\n",
+            "}\n",
+            "while (false) {}\n",
+        ));
+    }
+
+    #[test]
+    fn test_comments_and_blank_lines_are_kept() {
+        let source: &str = concat!(
+            "// Header.\n",
+            "var a = 1;   // one\n",
+            "\n\n\n",
+            "{\n",
+            "  /* inside */ print a;\n",
+            "  // before the brace\n",
+            "}\n",
+            "print a + // split\n",
+            "  2;\n",
+            "f(a /* first */, b /* second */);\n",
+            "var x = /* pre */ 1 /* post */;\n",
+            "print a /* object */.b;\n",
+        );
+
+        assert_eq!(formatted(source), concat!(
+            "// Header.\n",
+            "var a = 1; // one\n",
+            "\n",
+            "{\n",
+            "    /* inside */ print a;\n",
+            "    // before the brace\n",
+            "}\n",
+            "print a + // split\n",
+            "    2;\n",
+            "f(a /* first */, b /* second */);\n",
+            "var x = /* pre */ 1 /* post */;\n",
+            "print a /* object */.b;\n",
+        ));
+    }
+
+    #[test]
+    fn test_for_loops_and_literals_keep_their_spelling() {
+        let source: &str = "for(var i=0;i<0x10;i=i+1)print \"${ i }\\t${i*1_000}\";\nfor(;;){}\nfor(;i<2;)i=i+1;\n";
+
+        assert_eq!(formatted(source), concat!(
+            "for (var i = 0; i < 0x10; i = i + 1) print \"${i}\\t${i * 1_000}\";\n",
+            "for (;;) {}\n",
+            "for (; i < 2;) i = i + 1;\n",
+        ));
+    }
+
+    #[test]
+    fn test_example_is_stable() {
+        let once: String = formatted(include_str!("../examples/counter.lox"));
+        assert!(once.contains("counter(); //\"1\";\n"));
+    }
+
+    #[test]
+    fn test_parse_errors_are_returned() {
+        let diagnostics = format("print 1").unwrap_err();
+        assert_eq!(diagnostics[0].code, Code::ExpectedToken);
+    }
+
+    #[test]
+    fn test_losing_the_token_stream_is_an_internal_error() {
+        let parse = |source: &str| -> (Vec<Token>, Vec<Stmt>) {
+            let tokens: Vec<Token> = Scanner::new(String::from(source)).with_trivia(true).scan_tokens();
+            let statements: Vec<Stmt> = Parser::new(tokens.clone()).parse();
+            (tokens, statements)
+        };
+        let (tokens, _) = parse("print 1;\nvar a;\n");
+        let (_, statements) = parse("print 1;\nprint a;\n");
+
+        let mut formatter: Formatter = Formatter::new(tokens.clone());
+        for statement in &statements {
+            formatter.statement(statement);
+        }
+        let diagnostics = formatter.finish().unwrap_err();
+        assert_eq!(diagnostics[0].code, Code::Internal);
+        assert_eq!(diagnostics[0].span.line, 2);
+
+        // Stopping short of the end is caught as well.
+        let mut formatter: Formatter = Formatter::new(tokens);
+        formatter.statement(&statements[0]);
+        assert_eq!(formatter.finish().unwrap_err()[0].span.line, 2);
+    }
+}
@@ -0,0 +1,84 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::literal::Literal;
+use crate::stmt::Stmt;
+use crate::callable::LoxCallable;
+use crate::class::LoxInstance;
+use crate::environment::Environment;
+use crate::interpreter::Interpreter;
+use crate::value::Value;
+use crate::error::Error;
+
+/// A user-defined function together with the environment it closes over.
+pub struct LoxFunction {
+    name: Token,
+    params: Vec<Token>,
+    body: Rc<Vec<Stmt>>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>, closure: Rc<RefCell<Environment>>, is_initializer: bool) -> Self {
+        Self {
+            name: name.clone(),
+            params: params.to_vec(),
+            body: Rc::clone(body),
+            closure,
+            is_initializer,
+        }
+    }
+
+    /// Returns a copy of this method whose closure has `this` bound to `instance`.
+    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+        let mut environment: Environment = Environment::from(Rc::clone(&self.closure));
+        environment.define(String::from("this"), Value::Instance(instance));
+
+        LoxFunction::new(&self.name, &self.params, &self.body, Rc::new(RefCell::new(environment)), self.is_initializer)
+    }
+
+    fn this(&self) -> Result<Value, Error> {
+        let this: Token = Token::from(TokenType::This, String::from("this"), Literal::Empty, self.name.line);
+        self.closure.borrow().get_at(0, &this)
+    }
+}
+
+impl LoxCallable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    fn call(self: Rc<Self>, interpreter: &mut Interpreter, arguments: Vec<Value>) -> Result<Value, Error> {
+        let mut environment: Environment = Environment::from(Rc::clone(&self.closure));
+        for (param, argument) in self.params.iter().zip(arguments) {
+            environment.define(param.lexeme.clone(), argument);
+        }
+
+        match interpreter.execute_block(&self.body, Rc::new(RefCell::new(environment))) {
+            // An initializer always hands back the instance, even on a bare `return;`.
+            Ok(()) | Err(Error::Return(_)) if self.is_initializer => self.this(),
+            Ok(()) => Ok(Value::Nil),
+            Err(Error::Return(value)) => Ok(value),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+// The closure can refer back to this function, so Debug must not walk it.
+impl std::fmt::Debug for LoxFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoxFunction")
+            .field("name", &self.name.lexeme)
+            .field("arity", &self.params.len())
+            .finish()
+    }
+}
+
+impl std::fmt::Display for LoxFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fn {}>", self.name.lexeme)
+    }
+}
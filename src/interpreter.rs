@@ -0,0 +1,467 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::value::Value;
+use crate::environment::Environment;
+use crate::callable::{LoxCallable, Clock, Argc, Arg};
+use crate::function::LoxFunction;
+use crate::class::{LoxClass, LoxInstance};
+use crate::error::Error;
+
+/// How many calls may be in progress at once. Deeper recursion is reported
+/// as a runtime error before it can overflow the native stack.
+const MAX_CALL_DEPTH: usize = 1000;
+
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    locals: HashMap<usize, usize>,
+    environment: Rc<RefCell<Environment>>,
+    /// Calls currently in progress.
+    depth: usize,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        let globals: Rc<RefCell<Environment>> = Rc::new(RefCell::new(Environment::new()));
+        globals.borrow_mut().define(String::from("clock"), Value::Callable(Rc::new(Clock)));
+
+        Self {
+            environment: Rc::clone(&globals),
+            globals,
+            locals: HashMap::new(),
+            depth: 0,
+        }
+    }
+
+    /// Makes `args` available to scripts through the `argc()` and `arg(index)` natives.
+    pub fn with_args(self, args: Vec<String>) -> Self {
+        let args: Rc<Vec<String>> = Rc::new(args);
+        self.globals.borrow_mut().define(String::from("argc"), Value::Callable(Rc::new(Argc(Rc::clone(&args)))));
+        self.globals.borrow_mut().define(String::from("arg"), Value::Callable(Rc::new(Arg(args))));
+        self
+    }
+
+    /// Every global variable and its current value, sorted by name.
+    pub fn globals(&self) -> Vec<(String, Value)> {
+        self.globals.borrow().bindings()
+    }
+
+    pub fn interpret(&mut self, statements: &[Stmt]) -> Result<(), Error> {
+        for statement in statements {
+            self.execute(statement)?;
+        }
+
+        Ok(())
+    }
+
+    /// Records how many scopes out from the reference the variable `id` lives.
+    pub(crate) fn resolve(&mut self, id: usize, depth: usize) {
+        self.locals.insert(id, depth);
+    }
+
+    fn look_up_variable(&self, id: usize, name: &Token) -> Result<Value, Error> {
+        match self.locals.get(&id) {
+            Some(distance) => self.environment.borrow().get_at(*distance, name),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    fn execute(&mut self, stmt: &Stmt) -> Result<(), Error> {
+        stmt.accept(self)
+    }
+
+    pub(crate) fn execute_block(&mut self, statements: &[Stmt], environment: Rc<RefCell<Environment>>) -> Result<(), Error> {
+        let previous: Rc<RefCell<Environment>> = std::mem::replace(&mut self.environment, environment);
+
+        let result: Result<(), Error> = statements.iter().try_for_each(|statement| self.execute(statement));
+
+        self.environment = previous;
+        result
+    }
+
+    fn evaluate(&mut self, expr: &Expr) -> Result<Value, Error> {
+        expr.accept(self)
+    }
+
+    fn number_operand(&self, operator: &Token, operand: &Value) -> Result<f64, Error> {
+        match operand {
+            Value::Number(n) => Ok(*n),
+            _ => Err(Error::runtime(operator, "Operand must be a number.")),
+        }
+    }
+
+    fn number_operands(&self, operator: &Token, left: &Value, right: &Value) -> Result<(f64, f64), Error> {
+        match (left, right) {
+            (Value::Number(l), Value::Number(r)) => Ok((*l, *r)),
+            _ => Err(Error::runtime(operator, "Operands must be numbers.")),
+        }
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Visitor<Value> for Interpreter {
+    fn visit_assign_expr(&mut self, id: usize, name: &Token, value: &Expr) -> Result<Value, Error> {
+        let value: Value = self.evaluate(value)?;
+
+        match self.locals.get(&id) {
+            Some(distance) => self.environment.borrow_mut().assign_at(*distance, name, value.clone())?,
+            None => self.globals.borrow_mut().assign(name, value.clone())?,
+        }
+
+        Ok(value)
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<Value, Error> {
+        let left: Value = self.evaluate(left)?;
+        let right: Value = self.evaluate(right)?;
+
+        match operator.token_type {
+            TokenType::Minus => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Number(l - r))
+            },
+            TokenType::Slash => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Number(l / r))
+            },
+            TokenType::Star => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Number(l * r))
+            },
+            TokenType::Plus => match (left, right) {
+                (Value::Number(l), Value::Number(r)) => Ok(Value::Number(l + r)),
+                (Value::String(l), Value::String(r)) => Ok(Value::String(l + &r)),
+                _ => Err(Error::runtime(operator, "Operands must be two numbers or two strings.")),
+            },
+            TokenType::Greater => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Boolean(l > r))
+            },
+            TokenType::GreaterEqual => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Boolean(l >= r))
+            },
+            TokenType::Less => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Boolean(l < r))
+            },
+            TokenType::LessEqual => {
+                let (l, r) = self.number_operands(operator, &left, &right)?;
+                Ok(Value::Boolean(l <= r))
+            },
+            TokenType::BangEqual => Ok(Value::Boolean(left != right)),
+            TokenType::EqualEqual => Ok(Value::Boolean(left == right)),
+            _ => Err(Error::runtime(operator, "Unknown binary operator.")),
+        }
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> Result<Value, Error> {
+        let callee: Value = self.evaluate(callee)?;
+
+        let mut values: Vec<Value> = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            values.push(self.evaluate(argument)?);
+        }
+
+        let function: Rc<dyn LoxCallable> = match callee {
+            Value::Callable(function) => function,
+            Value::Class(class) => class,
+            _ => return Err(Error::runtime(paren, "Can only call functions and classes.")),
+        };
+
+        if values.len() != function.arity() {
+            let message: String = format!("Expected {} arguments but got {}.", function.arity(), values.len());
+            return Err(Error::runtime(paren, &message));
+        }
+
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(Error::runtime(paren, "Stack overflow."));
+        }
+
+        self.depth += 1;
+        let result: Result<Value, Error> = function.call(self, values);
+        self.depth -= 1;
+        result
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<Value, Error> {
+        match self.evaluate(object)? {
+            Value::Instance(instance) => LoxInstance::get(&instance, name),
+            _ => Err(Error::runtime(name, "Only instances have properties.")),
+        }
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<Value, Error> {
+        self.evaluate(expression)
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<Value, Error> {
+        let mut result: String = segments[0].clone();
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            result.push_str(&self.evaluate(expression)?.to_string());
+            result.push_str(segment);
+        }
+        Ok(Value::String(result))
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<Value, Error> {
+        Ok(Value::from(literal.clone()))
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<Value, Error> {
+        let left: Value = self.evaluate(left)?;
+
+        // Short-circuit, returning the operand that decided the result.
+        match operator.token_type {
+            TokenType::Or if left.is_truthy() => Ok(left),
+            TokenType::And if !left.is_truthy() => Ok(left),
+            _ => self.evaluate(right),
+        }
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<Value, Error> {
+        let instance: Rc<RefCell<LoxInstance>> = match self.evaluate(object)? {
+            Value::Instance(instance) => instance,
+            _ => return Err(Error::runtime(name, "Only instances have fields.")),
+        };
+
+        let value: Value = self.evaluate(value)?;
+        instance.borrow_mut().set(name, value.clone());
+        Ok(value)
+    }
+
+    fn visit_super_expr(&mut self, id: usize, keyword: &Token, method: &Token) -> Result<Value, Error> {
+        let distance: usize = *self.locals.get(&id).expect("'super' is always resolved to a local scope");
+
+        let superclass: Rc<LoxClass> = match self.environment.borrow().get_at(distance, keyword)? {
+            Value::Class(class) => class,
+            _ => return Err(Error::runtime(keyword, "Superclass must be a class.")),
+        };
+
+        // "this" always lives in the scope just inside the one holding "super".
+        let this: Token = Token::from(TokenType::This, String::from("this"), Literal::Empty, keyword.line);
+        let instance: Rc<RefCell<LoxInstance>> = match self.environment.borrow().get_at(distance - 1, &this)? {
+            Value::Instance(instance) => instance,
+            _ => return Err(Error::runtime(keyword, "'this' must be an instance.")),
+        };
+
+        match superclass.find_method(&method.lexeme) {
+            Some(found) => Ok(Value::Callable(Rc::new(found.bind(instance)))),
+            None => Err(Error::runtime(method, &format!("Undefined property '{}'.", method.lexeme))),
+        }
+    }
+
+    fn visit_this_expr(&mut self, id: usize, keyword: &Token) -> Result<Value, Error> {
+        self.look_up_variable(id, keyword)
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<Value, Error> {
+        let right: Value = self.evaluate(right)?;
+
+        match operator.token_type {
+            TokenType::Minus => Ok(Value::Number(-self.number_operand(operator, &right)?)),
+            TokenType::Bang => Ok(Value::Boolean(!right.is_truthy())),
+            _ => Err(Error::runtime(operator, "Unknown unary operator.")),
+        }
+    }
+
+    fn visit_variable_expr(&mut self, id: usize, name: &Token) -> Result<Value, Error> {
+        self.look_up_variable(id, name)
+    }
+}
+
+impl StmtVisitor<()> for Interpreter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), Error> {
+        let environment: Environment = Environment::from(Rc::clone(&self.environment));
+        self.execute_block(statements, Rc::new(RefCell::new(environment)))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<(), Error> {
+        let superclass: Option<Rc<LoxClass>> = match superclass {
+            Some(expr) => match self.evaluate(expr)? {
+                Value::Class(class) => Some(class),
+                _ => {
+                    let token: &Token = match expr {
+                        Expr::Variable { name, .. } => name,
+                        _ => name,
+                    };
+                    return Err(Error::runtime(token, "Superclass must be a class."));
+                },
+            },
+            None => None,
+        };
+
+        self.environment.borrow_mut().define(name.lexeme.clone(), Value::Nil);
+
+        let enclosing: Rc<RefCell<Environment>> = Rc::clone(&self.environment);
+        if let Some(superclass) = &superclass {
+            let mut environment: Environment = Environment::from(Rc::clone(&enclosing));
+            environment.define(String::from("super"), Value::Class(Rc::clone(superclass)));
+            self.environment = Rc::new(RefCell::new(environment));
+        }
+
+        let mut class_methods: HashMap<String, Rc<LoxFunction>> = HashMap::new();
+        for method in methods {
+            if let Stmt::Function { name, params, body } = method {
+                let is_initializer: bool = name.lexeme == "init";
+                let function: LoxFunction = LoxFunction::new(name, params, body, Rc::clone(&self.environment), is_initializer);
+                class_methods.insert(name.lexeme.clone(), Rc::new(function));
+            }
+        }
+
+        let class: LoxClass = LoxClass::new(name.lexeme.clone(), superclass, class_methods);
+        self.environment = enclosing;
+
+        self.environment.borrow_mut().assign(name, Value::Class(Rc::new(class)))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        self.evaluate(expression)?;
+        Ok(())
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<(), Error> {
+        let function: LoxFunction = LoxFunction::new(name, params, body, Rc::clone(&self.environment), false);
+        self.environment.borrow_mut().define(name.lexeme.clone(), Value::Callable(Rc::new(function)));
+        Ok(())
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<(), Error> {
+        if self.evaluate(condition)?.is_truthy() {
+            self.execute(then_branch)?;
+        } else if let Some(else_branch) = else_branch {
+            self.execute(else_branch)?;
+        }
+
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        let value: Value = self.evaluate(expression)?;
+        writeln!(std::io::stdout(), "{}", value)?;
+        Ok(())
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<(), Error> {
+        let value: Value = match value {
+            Some(expr) => self.evaluate(expr)?,
+            None => Value::Nil,
+        };
+
+        Err(Error::Return(value))
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<(), Error> {
+        let value: Value = match initializer {
+            Some(expr) => self.evaluate(expr)?,
+            None => Value::Nil,
+        };
+
+        self.environment.borrow_mut().define(name.lexeme.clone(), value);
+        Ok(())
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<(), Error> {
+        while self.evaluate(condition)?.is_truthy() {
+            self.execute(body)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::Token;
+    use crate::token_type::TokenType;
+    use crate::interpreter::Interpreter;
+    use crate::literal::Literal;
+    use crate::expr::Expr;
+    use crate::value::Value;
+    use crate::error::Error;
+
+    fn number(n: f64) -> Box<Expr> {
+        Box::new(Expr::Literal { literal: Literal::Number(n) })
+    }
+
+    fn string(s: &str) -> Box<Expr> {
+        Box::new(Expr::Literal { literal: Literal::String(String::from(s)) })
+    }
+
+    fn operator(token_type: TokenType, lexeme: &str) -> Token {
+        Token::from(token_type, String::from(lexeme), Literal::Empty, 1)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        // (1 + 2) * -3
+        let expression: Expr = Expr::Binary {
+            left: Box::new(Expr::Grouping { expression: Box::new(Expr::Binary { left: number(1.0), operator: operator(TokenType::Plus, "+"), right: number(2.0) }) }),
+            operator: operator(TokenType::Star, "*"),
+            right: Box::new(Expr::Unary { operator: operator(TokenType::Minus, "-"), right: number(3.0) }),
+        };
+
+        assert_eq!(Interpreter::new().evaluate(&expression).unwrap(), Value::Number(-9.0));
+    }
+
+    #[test]
+    fn test_truthiness_and_equality() {
+        let not_nil: Expr = Expr::Unary { operator: operator(TokenType::Bang, "!"), right: Box::new(Expr::Literal { literal: Literal::Empty }) };
+        assert_eq!(Interpreter::new().evaluate(&not_nil).unwrap(), Value::Boolean(true));
+
+        let equal: Expr = Expr::Binary { left: string("a"), operator: operator(TokenType::EqualEqual, "=="), right: string("a") };
+        assert_eq!(Interpreter::new().evaluate(&equal).unwrap(), Value::Boolean(true));
+
+        let mixed: Expr = Expr::Binary { left: number(1.0), operator: operator(TokenType::EqualEqual, "=="), right: string("1") };
+        assert_eq!(Interpreter::new().evaluate(&mixed).unwrap(), Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_logical_returns_deciding_operand() {
+        let or: Expr = Expr::Logical { left: Box::new(Expr::Literal { literal: Literal::Empty }), operator: operator(TokenType::Or, "or"), right: string("yes") };
+        assert_eq!(Interpreter::new().evaluate(&or).unwrap(), Value::String(String::from("yes")));
+
+        // The right operand would be a type error if it were evaluated.
+        let bad: Box<Expr> = Box::new(Expr::Unary { operator: operator(TokenType::Minus, "-"), right: string("x") });
+        let and: Expr = Expr::Logical { left: Box::new(Expr::Literal { literal: Literal::Boolean(false) }), operator: operator(TokenType::And, "and"), right: bad };
+        assert_eq!(Interpreter::new().evaluate(&and).unwrap(), Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_type_error_carries_token() {
+        let expression: Expr = Expr::Binary { left: string("a"), operator: operator(TokenType::Minus, "-"), right: number(1.0) };
+
+        match Interpreter::new().evaluate(&expression) {
+            Err(Error::Runtime { token, message }) => {
+                assert_eq!(token.token_type, TokenType::Minus);
+                assert_eq!(message, "Operands must be numbers.");
+            },
+            other => panic!("expected a runtime error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_undefined_variable_reports_identifier() {
+        let name: Token = Token::from(TokenType::Identifier, String::from("x"), Literal::Empty, 3);
+
+        match Interpreter::new().evaluate(&Expr::Variable { id: Expr::next_id(), name }) {
+            Err(Error::Runtime { token, message }) => {
+                assert_eq!(token.line, 3);
+                assert_eq!(message, "Undefined variable 'x'.");
+            },
+            other => panic!("expected a runtime error, got {:?}", other),
+        }
+    }
+}
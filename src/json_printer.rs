@@ -0,0 +1,213 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::error::Error;
+use crate::ast_printer::Printer;
+
+/// Version of the JSON schema below. Bump it whenever a field changes in a
+/// release.
+pub const SCHEMA_VERSION: usize = 1;
+
+/// Prints the AST as compact JSON for other tools to consume.
+///
+/// Every node is an object whose `"type"` is the variant name, followed by the
+/// variant's fields under their Rust names. Tokens become
+/// `{"lexeme": ..., "line": ..., "column": ...}`, missing optional children are
+/// `null`, and a program is `{"version": 1, "statements": [...]}`.
+pub struct JsonPrinter;
+
+impl JsonPrinter {
+    pub fn print(&mut self, expr: &Expr) -> Result<String, Error> {
+        expr.accept(self)
+    }
+
+    fn optional_expr(&mut self, expr: Option<&Expr>) -> Result<String, Error> {
+        match expr {
+            Some(expr) => expr.accept(self),
+            None => Ok(String::from("null")),
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[Expr]) -> Result<String, Error> {
+        let items: Vec<String> = expressions.iter().map(|expr| expr.accept(self)).collect::<Result<_, _>>()?;
+        Ok(format!("[{}]", items.join(",")))
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let items: Vec<String> = statements.iter().map(|statement| statement.accept(self)).collect::<Result<_, _>>()?;
+        Ok(format!("[{}]", items.join(",")))
+    }
+}
+
+fn object(node_type: &str, fields: Vec<(&str, String)>) -> String {
+    let mut result: String = format!("{{\"type\":{}", string(node_type));
+    for (name, value) in fields {
+        result.push_str(&format!(",{}:{}", string(name), value));
+    }
+    result.push('}');
+    result
+}
+
+fn token(token: &Token) -> String {
+    format!("{{\"lexeme\":{},\"line\":{},\"column\":{}}}", string(&token.lexeme), token.line, token.column)
+}
+
+fn tokens(tokens: &[Token]) -> String {
+    let items: Vec<String> = tokens.iter().map(token).collect();
+    format!("[{}]", items.join(","))
+}
+
+fn string(value: &str) -> String {
+    let mut result: String = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+impl Visitor<String> for JsonPrinter {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        Ok(object("Assign", vec![("name", token(name)), ("value", value.accept(self)?)]))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(object("Binary", vec![("left", left.accept(self)?), ("operator", token(operator)), ("right", right.accept(self)?)]))
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        Ok(object("Call", vec![("callee", callee.accept(self)?), ("paren", token(paren)), ("arguments", self.expressions(arguments)?)]))
+    }
+
+    fn visit_get_expr(&mut self, object_expr: &Expr, name: &Token) -> Result<String, Error> {
+        Ok(object("Get", vec![("object", object_expr.accept(self)?), ("name", token(name))]))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
+        Ok(object("Grouping", vec![("expression", expression.accept(self)?)]))
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let segments: Vec<String> = segments.iter().map(|segment| string(segment)).collect();
+        Ok(object("Interpolation", vec![("segments", format!("[{}]", segments.join(","))), ("expressions", self.expressions(expressions)?)]))
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
+        let value: String = match literal {
+            Literal::String(value) => string(value),
+            Literal::Number(value) if value.is_finite() => value.to_string(),
+            // JSON has no infinities, e.g. for `1e999`.
+            Literal::Number(_) => String::from("null"),
+            Literal::Boolean(value) => value.to_string(),
+            Literal::Empty => String::from("null"),
+        };
+        Ok(object("Literal", vec![("value", value)]))
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(object("Logical", vec![("left", left.accept(self)?), ("operator", token(operator)), ("right", right.accept(self)?)]))
+    }
+
+    fn visit_set_expr(&mut self, object_expr: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        Ok(object("Set", vec![("object", object_expr.accept(self)?), ("name", token(name)), ("value", value.accept(self)?)]))
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(object("Super", vec![("keyword", token(keyword)), ("method", token(method))]))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, keyword: &Token) -> Result<String, Error> {
+        Ok(object("This", vec![("keyword", token(keyword))]))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(object("Unary", vec![("operator", token(operator)), ("right", right.accept(self)?)]))
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(object("Variable", vec![("name", token(name))]))
+    }
+}
+
+impl StmtVisitor<String> for JsonPrinter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(object("Block", vec![("statements", self.statements(statements)?)]))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        Ok(object("Class", vec![("name", token(name)), ("superclass", self.optional_expr(superclass)?), ("methods", self.statements(methods)?)]))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(object("Expression", vec![("expression", expression.accept(self)?)]))
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        Ok(object("Function", vec![("name", token(name)), ("params", tokens(params)), ("body", self.statements(body)?)]))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let else_branch: String = match else_branch {
+            Some(else_branch) => else_branch.accept(self)?,
+            None => String::from("null"),
+        };
+        Ok(object("If", vec![("condition", condition.accept(self)?), ("then_branch", then_branch.accept(self)?), ("else_branch", else_branch)]))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(object("Print", vec![("expression", expression.accept(self)?)]))
+    }
+
+    fn visit_return_stmt(&mut self, keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        Ok(object("Return", vec![("keyword", token(keyword)), ("value", self.optional_expr(value)?)]))
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        Ok(object("Var", vec![("name", token(name)), ("initializer", self.optional_expr(initializer)?)]))
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        Ok(object("While", vec![("condition", condition.accept(self)?), ("body", body.accept(self)?)]))
+    }
+}
+
+impl Printer for JsonPrinter {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(format!("{{\"version\":{},\"statements\":{}}}\n", SCHEMA_VERSION, self.statements(statements)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::stmt::Stmt;
+    use crate::ast_printer::Printer;
+    use crate::json_printer::JsonPrinter;
+
+    #[test]
+    fn test_schema_includes_token_positions() {
+        let statements: Vec<Stmt> = Parser::new(Scanner::new(String::from("var a;\nprint -a == \"x\\n\";")).scan_tokens()).parse();
+
+        assert_eq!(JsonPrinter.print_program(&statements).unwrap(), concat!(
+            r#"{"version":1,"statements":["#,
+            r#"{"type":"Var","name":{"lexeme":"a","line":1,"column":5},"initializer":null},"#,
+            r#"{"type":"Print","expression":{"type":"Binary","#,
+            r#""left":{"type":"Unary","operator":{"lexeme":"-","line":2,"column":7},"right":{"type":"Variable","name":{"lexeme":"a","line":2,"column":8}}},"#,
+            r#""operator":{"lexeme":"==","line":2,"column":10},"#,
+            r#""right":{"type":"Literal","value":"x\n"}}}"#,
+            "]}\n",
+        ));
+    }
+}
@@ -7,103 +7,355 @@ pub mod token_type;
 pub mod literal;
 pub mod scanner;
 pub mod expr;
+pub mod stmt;
 pub mod error;
+pub mod diagnostic;
+pub mod render;
 pub mod ast_printer;
+pub mod rpn_printer;
+pub mod tree_printer;
+pub mod dot_printer;
+pub mod json_printer;
+pub mod unparser;
+pub mod formatter;
+pub mod repl;
 pub mod parser;
+pub mod value;
+pub mod environment;
+pub mod callable;
+pub mod function;
+pub mod class;
+pub mod interpreter;
+pub mod resolver;
 
 use token::Token;
-use token_type::TokenType;
-use expr::Expr;
+use stmt::Stmt;
 use scanner::Scanner;
 use parser::Parser;
-use ast_printer::AstPrinter;
+use interpreter::Interpreter;
+use resolver::Resolver;
+use error::Error;
+use diagnostic::{Code, Diagnostic, Span};
+use render::Renderer;
+
+const USAGE: &str = "\
+Usage: rlox [command] [arguments]
+
+Commands:
+    rlox                                  Start the interactive prompt
+    rlox repl                             Start the interactive prompt
+    rlox run <file> [args...]             Run a script
+    rlox <file> [args...]                 Shorthand for `rlox run`
+    rlox -e <source> [args...]            Run source given on the command line,
+                                          printing the value of a bare expression
+    rlox eval <source> [args...]          Same as `rlox -e`
+    rlox tokens <file>                    Print the tokens of a script
+    rlox ast [--format=<format>] <file>   Print the syntax tree of a script
+    rlox check <file>                     Report errors in a script without running it
+    rlox fmt [--check] <file>...          Rewrite scripts in the canonical format,
+                                          or print it for `-`
+
+A <file> of `-` reads the script from standard input. Scripts read their
+arguments with `argc()` and `arg(index)`.
+
+Options:
+    -h, --help       Print this help
+    -V, --version    Print the version
+";
+
+/// Exit statuses, following the BSD `sysexits.h` conventions.
+const EX_USAGE: i32 = 64;
+const EX_DATAERR: i32 = 65;
+const EX_NOINPUT: i32 = 66;
+const EX_SOFTWARE: i32 = 70;
+const EX_IOERR: i32 = 74;
 
 pub struct Lox {
-    had_error: bool
+    diagnostics: Vec<Diagnostic>,
+    file_name: String,
+    source: String,
+    /// Kept for the whole session, so that REPL entries see the globals
+    /// defined by earlier ones.
+    interpreter: Interpreter,
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Lox {
     pub fn new() -> Self {
         Self {
-            had_error: false,
+            diagnostics: vec![],
+            file_name: String::from("<stdin>"),
+            source: String::new(),
+            interpreter: Interpreter::new(),
         }
     }
 
+    /// Runs the command in `args` and exits with a `sysexits.h` status if
+    /// it fails.
     pub fn main(&mut self, args: Vec<String>) {
-        match args.len() {
-            1 => self.run_file(&args[0]).unwrap(),
-            0 => self.run_prompt().unwrap(),
-            _ => {
-                eprintln!("Usage: rlox [script]");
-                std::process::exit(1);
+        let result: io::Result<()> = match args.first().map(String::as_str) {
+            None => self.run_prompt(),
+            Some("-h" | "--help") => write!(io::stdout(), "{}", USAGE),
+            Some("-V" | "--version") => writeln!(io::stdout(), "rlox {}", env!("CARGO_PKG_VERSION")),
+            Some("repl") if args.len() == 1 => self.run_prompt(),
+            Some("run") if args.len() > 1 => self.run_file(&args[1], &args[2..]),
+            Some("tokens") if args.len() == 2 => self.print_tokens(&args[1]),
+            Some("ast") => self.print_ast(&args[1..]),
+            Some("check") if args.len() == 2 => self.check_file(&args[1]),
+            Some("fmt") => self.format_files(&args[1..]),
+            Some("-e" | "eval") if args.len() > 1 => {
+                self.file_name = String::from("<eval>");
+                self.interpreter = Interpreter::new().with_args(args[2..].to_vec());
+                self.eval(args[1].clone()).map(|()| self.exit_on_errors())
+            },
+            Some("repl" | "run" | "tokens" | "check" | "-e" | "eval") => usage(),
+            Some(command) if command.starts_with('-') && command != "-" => usage(),
+            Some(command) => self.run_file(command, &args[1..]),
+        };
+
+        if let Err(error) = result {
+            eprintln!("rlox: {}", error);
+            match error.kind() {
+                io::ErrorKind::NotFound => std::process::exit(EX_NOINPUT),
+                _ => std::process::exit(EX_IOERR),
             }
         }
     }
 
-    fn run_file(&mut self, file_path: &String) -> std::io::Result<()> {
-        let mut file: File = File::open(file_path)?;
-        let mut contents: String = String::new();
-        file.read_to_string(&mut contents)?;
+    /// Reads the script at `file_path`, or standard input for `-`, into
+    /// [`Lox::source`].
+    fn read_input(&mut self, file_path: &str) -> std::io::Result<()> {
+        self.source = match file_path {
+            "-" => {
+                let mut contents: String = String::new();
+                io::stdin().read_to_string(&mut contents).map_err(context("can't read", "standard input"))?;
+                self.file_name = String::from("<stdin>");
+                contents
+            },
+            _ => {
+                let mut file: File = File::open(file_path).map_err(context("can't open", file_path))?;
+                let mut contents: String = String::new();
+                file.read_to_string(&mut contents).map_err(context("can't read", file_path))?;
+                self.file_name = String::from(file_path);
+                contents
+            },
+        };
+
+        Ok(())
+    }
+
+    fn run_file(&mut self, file_path: &str, args: &[String]) -> std::io::Result<()> {
+        self.read_input(file_path)?;
+        self.interpreter = Interpreter::new().with_args(args.to_vec());
+
+        self.run(self.source.clone())?;
+        self.exit_on_errors();
+
+        Ok(())
+    }
+
+    /// Prints each token of a script on its own line, prefixed by its position.
+    fn print_tokens(&mut self, file_path: &str) -> std::io::Result<()> {
+        self.read_input(file_path)?;
+
+        let mut scanner: Scanner = Scanner::new(self.source.clone());
+        let tokens: Vec<Token> = scanner.scan_tokens();
+        self.diagnostics.extend(scanner.take_diagnostics());
+
+        let mut stdout: io::StdoutLock = io::stdout().lock();
+        for token in tokens {
+            writeln!(stdout, "{}:{} {}", token.line, token.column, token)?;
+        }
+        self.exit_on_errors();
+
+        Ok(())
+    }
+
+    /// Scans, parses and resolves a script without running it.
+    fn check_file(&mut self, file_path: &str) -> std::io::Result<()> {
+        self.read_input(file_path)?;
+
+        let statements: Vec<Stmt> = self.parse();
+        if !self.had_error() {
+            let mut interpreter: Interpreter = Interpreter::new();
+            let mut resolver: Resolver = Resolver::new(&mut interpreter);
+            resolver.resolve(&statements);
+            self.diagnostics.extend(resolver.take_diagnostics());
+        }
+        self.exit_on_errors();
+
+        Ok(())
+    }
+
+    /// Rewrites each file in its canonical format, or prints it for `-`. With
+    /// `--check`, lists the files that aren't formatted instead and exits
+    /// with 1 if there are any.
+    fn format_files(&mut self, args: &[String]) -> std::io::Result<()> {
+        let check: bool = args.iter().any(|arg| arg == "--check");
+        let files: Vec<&String> = args.iter().filter(|arg| *arg != "--check").collect();
+
+        if files.is_empty() {
+            eprintln!("Usage: rlox fmt [--check] <file>...");
+            std::process::exit(EX_USAGE);
+        }
 
-        self.run(contents);
+        let mut unformatted: bool = false;
+        for file_path in files {
+            self.read_input(file_path)?;
 
-        if self.had_error { std::process::exit(65); }
+            match formatter::format(&self.source) {
+                Ok(formatted) if check && formatted != self.source => {
+                    eprintln!("Would reformat: {}", self.file_name);
+                    unformatted = true;
+                },
+                Ok(_) if check => (),
+                Ok(formatted) if file_path == "-" => write!(io::stdout(), "{}", formatted)?,
+                Ok(formatted) if formatted == self.source => (),
+                Ok(formatted) => std::fs::write(file_path, formatted).map_err(context("can't write", file_path))?,
+                Err(diagnostics) => {
+                    self.diagnostics = diagnostics;
+                    self.exit_on_errors();
+                },
+            }
+        }
+
+        if unformatted { std::process::exit(1); }
 
         Ok(())
     }
 
-    fn run_prompt(&mut self) -> std::io::Result<()> {
-        loop {
-            print!("> ");
-            let _ = io::stdout().flush();
-            let mut buffer = String::new();
-            match io::stdin().read_line(&mut buffer) {
-                Ok(0) => return Ok(()), //end-of-line Control-D 
-                Ok(_) => {},
-                Err(error) => return Err(error),
-            };
-            
-            //Maybe need to remove this later - removes /n from line.
-            buffer = buffer.trim().to_string();
-            
-            self.run(buffer);
+    /// Parses a file and prints its syntax tree in the chosen output format.
+    fn print_ast(&mut self, args: &[String]) -> std::io::Result<()> {
+        let usage = || -> ! {
+            eprintln!("Usage: rlox ast [--format=<format>] <file>");
+            eprintln!("Formats: {}", ast_printer::FORMATS.join(", "));
+            std::process::exit(EX_USAGE);
+        };
+
+        let mut format: &str = "sexpr";
+        let mut file_path: Option<&String> = None;
+        for arg in args {
+            match arg.strip_prefix("--format=") {
+                Some(name) => format = name,
+                None if file_path.is_none() => file_path = Some(arg),
+                None => usage(),
+            }
+        }
+
+        let Some(mut printer) = ast_printer::printer(format) else { usage() };
+        let Some(file_path) = file_path else { usage() };
+
+        self.read_input(file_path)?;
+        let statements: Vec<Stmt> = self.parse();
+        self.exit_on_errors();
+
+        match printer.print_program(&statements) {
+            Ok(output) => write!(io::stdout(), "{}", output).map_err(context("can't write", "standard output")),
+            Err(Error::Io(error)) => Err(context("can't write", "standard output")(error)),
+            Err(_) => {
+                let message: String = format!("Couldn't print the syntax tree as {}. This is a bug in rlox.", format);
+                self.report(Diagnostic::error(Code::Internal, message, Span::default()));
+                self.exit_on_errors();
+                Ok(())
+            },
         }
     }
 
-    fn run(&mut self, source: String) {
-        let mut scanner: Scanner = Scanner::new(source, self);
+    /// Scans and parses [`Lox::source`], collecting any diagnostics.
+    fn parse(&mut self) -> Vec<Stmt> {
+        let mut scanner: Scanner = Scanner::new(self.source.clone());
         let tokens: Vec<Token> = scanner.scan_tokens();
-        
-        let mut parser: Parser = Parser::new(tokens, self);
-        let expression: Expr = parser.parse();
-        
-        if self.had_error { return; }
+        self.diagnostics.extend(scanner.take_diagnostics());
+
+        let mut parser: Parser = Parser::new(tokens);
+        let statements: Vec<Stmt> = parser.parse();
+        self.diagnostics.extend(parser.take_diagnostics());
+        statements
+    }
 
-        let mut ast_printer = AstPrinter {};
-        let print_result = ast_printer.print(expression).unwrap();
+    /// Scans, parses, resolves and interprets `source`, collecting every
+    /// problem into [`Lox::diagnostics`] rather than printing it. Only a
+    /// failure to write the script's output is returned as an error.
+    pub fn run(&mut self, source: String) -> io::Result<()> {
+        self.source = source;
 
-        println!("{}", print_result);
+        let statements: Vec<Stmt> = self.parse();
+        self.execute(&statements)
     }
 
-    fn error(&mut self, line: usize, message: String) {
-        self.report(line, String::new(), message);
+    /// Resolves and interprets `statements` with the session's interpreter,
+    /// unless parsing them reported an error.
+    fn execute(&mut self, statements: &[Stmt]) -> io::Result<()> {
+        if self.had_error() { return Ok(()); }
+
+        let mut resolver: Resolver = Resolver::new(&mut self.interpreter);
+        resolver.resolve(statements);
+        self.diagnostics.extend(resolver.take_diagnostics());
+
+        if self.had_error() { return Ok(()); }
+
+        match self.interpreter.interpret(statements) {
+            Err(Error::Io(error)) => return Err(context("can't write", "standard output")(error)),
+            Err(error) => self.runtime_error(error),
+            Ok(()) => (),
+        }
+        Ok(())
+    }
+
+    /// Prints the collected diagnostics, then exits with 65 if any of them
+    /// is a compile error, or 70 if the script failed at runtime or rlox
+    /// itself went wrong.
+    fn exit_on_errors(&self) {
+        self.print_diagnostics();
+        if self.had_error() { std::process::exit(EX_DATAERR); }
+        if self.diagnostics.iter().any(Diagnostic::is_error) { std::process::exit(EX_SOFTWARE); }
     }
 
-    fn report(&mut self, line: usize, location: String, message: String) {
-        eprintln!(
-            "[line {}] Error {}: {}", line, location, message
-        );
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// True when a compile-time (scan, parse or resolve) error was reported.
+    pub fn had_error(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.is_error() && !matches!(diagnostic.code, Code::Runtime | Code::Internal))
+    }
 
-        self.had_error = true;
+    pub fn had_runtime_error(&self) -> bool {
+        self.diagnostics.iter().any(|diagnostic| diagnostic.code == Code::Runtime)
     }
 
-    fn parse_error(&mut self, token: Token, message: String) {
-        if token.token_type == TokenType::Eof {
-            eprintln!("{} at end {}", token.line, message);
-        } else {
-            eprintln!("{} at '{}'{}", token.line, token.lexeme, message);
+    fn print_diagnostics(&self) {
+        let renderer: Renderer = Renderer::new(&self.file_name, &self.source)
+            .with_color(Renderer::stderr_supports_color());
+
+        for diagnostic in &self.diagnostics {
+            eprint!("{}", renderer.render(diagnostic));
         }
+    }
+
+    fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
 
-        self.had_error = true;
+    fn runtime_error(&mut self, error: Error) {
+        if let Error::Runtime { token, message } = error {
+            self.report(Diagnostic::error(Code::Runtime, message, Span::from(token.as_ref())));
+        }
     }
 }
+
+fn usage() -> ! {
+    eprint!("{}", USAGE);
+    std::process::exit(EX_USAGE);
+}
+
+/// Prefixes an I/O error with what was being done to which file, keeping its
+/// kind so that [`Lox::main`] can still pick the exit status from it.
+fn context<'a>(action: &'a str, path: &'a str) -> impl FnOnce(io::Error) -> io::Error + 'a {
+    move |error| io::Error::new(error.kind(), format!("{} '{}': {}", action, path, error))
+}
@@ -1,9 +1,20 @@
 use lox_rs::Lox;
 
+/// Room for the interpreter's call depth limit even in debug builds, whose
+/// frames are much larger; the main thread's default stack is too small.
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
 fn main() {
     let mut args: Vec<String> = std::env::args().collect();
     args.remove(0);
-    println!("{:?}", &args);
-    let mut lox_interp: Lox = Lox::new();
-    lox_interp.main(args);
-} 
+
+    let interpreter = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            let mut lox_interp: Lox = Lox::new();
+            lox_interp.main(args);
+        });
+
+    if interpreter.map(|handle| handle.join().is_ok()).unwrap_or(false) { return; }
+    std::process::exit(70);
+}
@@ -1,89 +1,384 @@
+use std::rc::Rc;
+
 use crate::token::Token;
 use crate::token_type::TokenType;
 use crate::literal::Literal;
 use crate::expr::Expr;
+use crate::stmt::Stmt;
 use crate::error::Error;
-use crate::Lox;
+use crate::diagnostic::{Code, Diagnostic};
+
+/// How deeply statements and expressions may nest, so that a pathological
+/// script gets a diagnostic instead of overflowing the stack here or in the
+/// passes that walk the tree afterwards.
+const MAX_DEPTH: usize = 256;
 
-pub struct Parser<'a> {
+pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
-    lox: &'a mut Lox,
+    diagnostics: Vec<Diagnostic>,
+    /// Statements and expressions currently being parsed inside one another.
+    depth: usize,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(tokens: Vec<Token>, lox: &'a mut Lox) -> Self {
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             tokens,
             current: 0usize,
-            lox,
+            diagnostics: vec![],
+            depth: 0,
+        }
+    }
+
+    pub fn parse(&mut self) -> Vec<Stmt> {
+        let mut statements: Vec<Stmt> = vec![];
+
+        while !self.is_at_end() {
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(_) => self.synchronize(),
+            }
+        }
+
+        statements
+    }
+
+    /// Every syntax error reported by [`Parser::parse`], in source order.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn declaration(&mut self) -> Result<Stmt, Error> {
+        if self.trial( vec![TokenType::Class] ) { return self.class_declaration(); }
+        if self.trial( vec![TokenType::Fun] ) { return self.function(String::from("function")); }
+        if self.trial( vec![TokenType::Var] ) { return self.var_declaration(); }
+
+        self.statement()
+    }
+
+    fn class_declaration(&mut self) -> Result<Stmt, Error> {
+        let name: Token = self.consume(TokenType::Identifier, String::from("Expect class name."))?;
+
+        let superclass: Option<Expr> = match self.trial( vec![TokenType::Less] ) {
+            true => {
+                let superclass_name: Token = self.consume(TokenType::Identifier, String::from("Expect superclass name."))?;
+                Some(Expr::Variable { id: Expr::next_id(), name: superclass_name })
+            },
+            false => None,
+        };
+
+        self.consume(TokenType::LeftBrace, String::from("Expect '{' before class body."))?;
+
+        let mut methods: Vec<Stmt> = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.function(String::from("method"))?);
+        }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after class body."))?;
+        Ok(Stmt::Class { name, superclass, methods })
+    }
+
+    fn function(&mut self, kind: String) -> Result<Stmt, Error> {
+        self.nested_statement(|parser| parser.function_declaration(kind))
+    }
+
+    fn function_declaration(&mut self, kind: String) -> Result<Stmt, Error> {
+        let name: Token = self.consume(TokenType::Identifier, format!("Expect {} name.", kind))?;
+        self.consume(TokenType::LeftParen, format!("Expect '(' after {} name.", kind))?;
+
+        let mut params: Vec<Token> = vec![];
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if params.len() >= 255 {
+                    self.error(Code::TooManyArguments, self.peek().clone(), String::from("Can't have more than 255 parameters."));
+                }
+
+                params.push(self.consume(TokenType::Identifier, String::from("Expect parameter name."))?);
+
+                if !self.trial( vec![TokenType::Comma] ) { break; }
+            }
+        }
+        self.consume(TokenType::RightParen, String::from("Expect ')' after parameters."))?;
+
+        self.consume(TokenType::LeftBrace, format!("Expect '{{' before {} body.", kind))?;
+        let body: Vec<Stmt> = self.block()?;
+
+        Ok(Stmt::Function { name, params, body: Rc::new(body) })
+    }
+
+    fn var_declaration(&mut self) -> Result<Stmt, Error> {
+        let name: Token = self.consume(TokenType::Identifier, String::from("Expect variable name."))?;
+
+        let initializer: Option<Expr> = match self.trial( vec![TokenType::Equal] ) {
+            true => Some(self.expression()?),
+            false => None,
+        };
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after variable declaration."))?;
+        Ok(Stmt::Var { name, initializer })
+    }
+
+    fn statement(&mut self) -> Result<Stmt, Error> {
+        self.nested_statement(Self::any_statement)
+    }
+
+    fn any_statement(&mut self) -> Result<Stmt, Error> {
+        if self.trial( vec![TokenType::For] ) { return self.for_statement(); }
+        if self.trial( vec![TokenType::If] ) { return self.if_statement(); }
+        if self.trial( vec![TokenType::Print] ) { return self.print_statement(); }
+        if self.trial( vec![TokenType::Return] ) { return self.return_statement(); }
+        if self.trial( vec![TokenType::While] ) { return self.while_statement(); }
+        if self.trial( vec![TokenType::LeftBrace] ) { return Ok(Stmt::Block { statements: self.block()? }); }
+
+        self.expression_statement()
+    }
+
+    /// Desugars `for (init; cond; incr) body` into `{ init; while (cond) { body; incr; } }`.
+    fn for_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'for'."))?;
+
+        let initializer: Option<Stmt> = if self.trial( vec![TokenType::Semicolon] ) {
+            None
+        } else if self.trial( vec![TokenType::Var] ) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition: Option<Expr> = match self.check(TokenType::Semicolon) {
+            true => None,
+            false => Some(self.expression()?),
+        };
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after loop condition."))?;
+
+        let increment: Option<Expr> = match self.check(TokenType::RightParen) {
+            true => None,
+            false => Some(self.expression()?),
+        };
+        self.consume(TokenType::RightParen, String::from("Expect ')' after for clauses."))?;
+
+        let mut body: Stmt = self.statement()?;
+
+        if let Some(increment) = increment {
+            body = Stmt::Block { statements: vec![body, Stmt::Expression { expression: increment }] };
+        }
+
+        let condition: Expr = condition.unwrap_or(Expr::Literal { literal: Literal::Boolean(true) });
+        body = Stmt::While { condition, body: Box::new(body) };
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block { statements: vec![initializer, body] };
+        }
+
+        Ok(body)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'if'."))?;
+        let condition: Expr = self.expression()?;
+        self.consume(TokenType::RightParen, String::from("Expect ')' after if condition."))?;
+
+        let then_branch: Box<Stmt> = Box::new(self.statement()?);
+        let else_branch: Option<Box<Stmt>> = match self.trial( vec![TokenType::Else] ) {
+            true => Some(Box::new(self.statement()?)),
+            false => None,
+        };
+
+        Ok(Stmt::If { condition, then_branch, else_branch })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, Error> {
+        let keyword: Token = self.previous().clone();
+        let value: Option<Expr> = match self.check(TokenType::Semicolon) {
+            true => None,
+            false => Some(self.expression()?),
+        };
+
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after return value."))?;
+        Ok(Stmt::Return { keyword, value })
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, Error> {
+        self.consume(TokenType::LeftParen, String::from("Expect '(' after 'while'."))?;
+        let condition: Expr = self.expression()?;
+        self.consume(TokenType::RightParen, String::from("Expect ')' after condition."))?;
+        let body: Stmt = self.statement()?;
+
+        Ok(Stmt::While { condition, body: Box::new(body) })
+    }
+
+    fn print_statement(&mut self) -> Result<Stmt, Error> {
+        let value: Expr = self.expression()?;
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after value."))?;
+        Ok(Stmt::Print { expression: value })
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt, Error> {
+        let expr: Expr = self.expression()?;
+        self.consume(TokenType::Semicolon, String::from("Expect ';' after expression."))?;
+        Ok(Stmt::Expression { expression: expr })
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut statements: Vec<Stmt> = vec![];
+
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(_) => self.synchronize(),
+            }
         }
+
+        self.consume(TokenType::RightBrace, String::from("Expect '}' after block."))?;
+        Ok(statements)
     }
 
-    pub fn parse(&mut self) -> Expr {
-        self.expression()
+    fn expression(&mut self) -> Result<Expr, Error> {
+        self.nested(Self::assignment)
+    }
+
+    fn assignment(&mut self) -> Result<Expr, Error> {
+        let expr: Expr = self.or()?;
+
+        if self.trial( vec![TokenType::Equal] ) {
+            let equals: Token = self.previous().clone();
+            let value: Expr = self.nested(Self::assignment)?;
+
+            return match expr {
+                Expr::Variable { name, .. } => Ok(Expr::Assign { id: Expr::next_id(), name, value: Box::new(value) }),
+                Expr::Get { object, name } => Ok(Expr::Set { object, name, value: Box::new(value) }),
+                _ => {
+                    // Reported but not thrown: the parser is not confused, so no need to synchronize.
+                    self.error(Code::InvalidAssignmentTarget, equals, String::from("Invalid assignment target."));
+                    Ok(expr)
+                },
+            };
+        }
+
+        Ok(expr)
     }
 
-    fn expression(&mut self) -> Expr {
-        self.equality()
+    fn or(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.and()?;
+
+        while self.trial( vec![TokenType::Or] ) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.and()?;
+            expr = Expr::Logical { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.equality()?;
+
+        while self.trial( vec![TokenType::And] ) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.equality()?;
+            expr = Expr::Logical { left: Box::new(expr), operator, right: Box::new(right) };
+        }
+
+        Ok(expr)
     }
 
-    fn equality(&mut self) -> Expr {
-        let mut expr: Expr = self.comparison();
+    fn equality(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.comparison()?;
 
         while self.trial( vec![TokenType::BangEqual, TokenType::EqualEqual] ) {
-            let operator: Token = self.previous();
-            let right: Expr = self.comparison();
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.comparison()?;
             expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn comparison(&mut self) -> Expr {
-        let mut expr: Expr = self.term();
+    fn comparison(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.term()?;
 
         while self.trial( vec![TokenType::Greater, TokenType::GreaterEqual, TokenType::Less, TokenType::LessEqual] ) {
-            let operator: Token = self.previous();
-            let right: Expr = self.term();
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.term()?;
             expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn term(&mut self) -> Expr {
-        let mut expr: Expr = self.factor();
+    fn term(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.factor()?;
 
         while self.trial( vec![TokenType::Plus, TokenType::Minus] ) {
-            let operator: Token = self.previous();
-            let right: Expr = self.factor();
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.factor()?;
             expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn factor(&mut self) -> Expr {
-        let mut expr: Expr = self.unary();
+    fn factor(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.unary()?;
 
         while self.trial( vec![TokenType::Slash, TokenType::Star] ) {
-            let operator: Token = self.previous();
-            let right: Expr = self.unary();
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.unary()?;
             expr = Expr::Binary { left: Box::new(expr), operator, right: Box::new(right) };
         }
 
-        expr
+        Ok(expr)
     }
 
-    fn unary(&mut self) -> Expr {
-        while self.trial( vec![TokenType::Bang, TokenType::Minus] ) {
-            let operator: Token = self.previous();
-            let right: Expr = self.unary();
-            return Expr::Unary { operator, right: Box::new(right) }
+    fn unary(&mut self) -> Result<Expr, Error> {
+        if self.trial( vec![TokenType::Bang, TokenType::Minus] ) {
+            let operator: Token = self.previous().clone();
+            let right: Expr = self.nested(Self::unary)?;
+            return Ok(Expr::Unary { operator, right: Box::new(right) });
         }
 
-        self.primary().expect("Unknown Token while parsing")
+        self.call()
+    }
+
+    fn call(&mut self) -> Result<Expr, Error> {
+        let mut expr: Expr = self.primary()?;
+
+        loop {
+            if self.trial( vec![TokenType::LeftParen] ) {
+                expr = self.finish_call(expr)?;
+            } else if self.trial( vec![TokenType::Dot] ) {
+                let name: Token = self.consume(TokenType::Identifier, String::from("Expect property name after '.'."))?;
+                expr = Expr::Get { object: Box::new(expr), name };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, Error> {
+        let mut arguments: Vec<Expr> = vec![];
+
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if arguments.len() >= 255 {
+                    self.error(Code::TooManyArguments, self.peek().clone(), String::from("Can't have more than 255 arguments."));
+                }
+
+                arguments.push(self.expression()?);
+
+                if !self.trial( vec![TokenType::Comma] ) { break; }
+            }
+        }
+
+        let paren: Token = self.consume(TokenType::RightParen, String::from("Expect ')' after arguments."))?;
+        Ok(Expr::Call { callee: Box::new(callee), paren, arguments })
     }
 
     fn primary(&mut self) -> Result<Expr, Error> {
@@ -91,22 +386,104 @@ impl<'a> Parser<'a> {
         if self.trial( vec![TokenType::True] ) { return Ok(Expr::Literal { literal: Literal::Boolean(true) }); }
         if self.trial( vec![TokenType::Nil] ) { return Ok(Expr::Literal { literal: Literal::Empty }); }
         if self.trial( vec![TokenType::Number, TokenType::String] ) { 
-            return Ok(Expr::Literal { literal: self.previous().literal });
+            return Ok(Expr::Literal { literal: self.previous().literal.clone() });
+        }
+        if self.trial( vec![TokenType::Interpolation] ) { return self.interpolation(); }
+        if self.trial( vec![TokenType::Super] ) {
+            let keyword: Token = self.previous().clone();
+            self.consume(TokenType::Dot, String::from("Expect '.' after 'super'."))?;
+            let method: Token = self.consume(TokenType::Identifier, String::from("Expect superclass method name."))?;
+            return Ok(Expr::Super { id: Expr::next_id(), keyword, method });
+        }
+        if self.trial( vec![TokenType::This] ) {
+            return Ok(Expr::This { id: Expr::next_id(), keyword: self.previous().clone() });
+        }
+        if self.trial( vec![TokenType::Identifier] ) {
+            return Ok(Expr::Variable { id: Expr::next_id(), name: self.previous().clone() });
         }
         if self.trial( vec![TokenType::LeftParen] ) { 
-            let expr: Expr = self.expression();
-            self.consume(TokenType::RightParen, String::from("Expect ')' after expression.")).expect("Error parsing [DEBUG] to be changed soon");
+            let expr: Expr = self.expression()?;
+            self.consume(TokenType::RightParen, String::from("Expect ')' after expression."))?;
             return Ok(Expr::Grouping { expression: Box::new(expr) });
         }
 
-        self.lox.parse_error(self.peek(), String::from("Expected expression."));
-        Err(Error::Parser)
+        Err(self.error(Code::ExpectedExpression, self.peek().clone(), String::from("Expected expression.")))
+    }
+
+    /// Parses `"a ${x} b"`, which the scanner splits into an `Interpolation`
+    /// token for each segment that ends in `${` and a `String` token for the
+    /// final one.
+    fn interpolation(&mut self) -> Result<Expr, Error> {
+        let mut segments: Vec<String> = vec![segment(self.previous())];
+        let mut expressions: Vec<Expr> = vec![];
+
+        loop {
+            // In `"${}"` the closing segment would otherwise be taken for the
+            // expression. Unlike a nested string, it starts with the `}`.
+            let closes: bool = matches!(self.peek().token_type, TokenType::String | TokenType::Interpolation);
+            if closes && self.peek().lexeme.starts_with('}') {
+                return Err(self.error(Code::ExpectedExpression, self.peek().clone(), String::from("Expected expression.")));
+            }
+            expressions.push(self.expression()?);
+
+            let next: Token = match self.trial( vec![TokenType::Interpolation] ) {
+                true => self.previous().clone(),
+                false => self.consume(TokenType::String, String::from("Expect '}' after interpolated expression."))?,
+            };
+            let is_last: bool = next.token_type == TokenType::String;
 
+            segments.push(segment(&next));
+            if is_last { return Ok(Expr::Interpolation { segments, expressions }); }
+        }
     }
 
     fn consume(&mut self, token_type: TokenType, message: String) -> Result<Token, Error> {
-        if self.check(token_type) { return Ok(self.advance()); }
-        Err(self.error(self.peek(), message))
+        if self.check(token_type) { return Ok(self.advance().clone()); }
+        Err(self.error(Code::ExpectedToken, self.peek().clone(), message))
+    }
+
+    /// Runs `parse` one level deeper, or reports that the script nests too
+    /// deeply once [`MAX_DEPTH`] is reached.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T, Error>) -> Result<T, Error> {
+        if self.depth >= MAX_DEPTH { return Err(self.too_deep()); }
+
+        self.depth += 1;
+        let result: Result<T, Error> = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Like [`Parser::nested`], except that a statement nesting too deeply is
+    /// skipped whole rather than thrown, so that recovering from it can't
+    /// swallow the `}` of every block around it.
+    fn nested_statement(&mut self, parse: impl FnOnce(&mut Self) -> Result<Stmt, Error>) -> Result<Stmt, Error> {
+        if self.depth < MAX_DEPTH { return self.nested(parse); }
+
+        self.too_deep();
+        self.skip_statement();
+        Ok(Stmt::Block { statements: vec![] })
+    }
+
+    fn too_deep(&mut self) -> Error {
+        self.error(Code::TooDeeplyNested, self.peek().clone(), String::from("Too much nesting."))
+    }
+
+    /// Skips past the statement starting at the current token, brackets and
+    /// all, stopping short of a `)` or `}` that closes something around it.
+    fn skip_statement(&mut self) {
+        let mut open: usize = 0;
+
+        while !self.is_at_end() {
+            match self.peek().token_type {
+                TokenType::LeftParen | TokenType::LeftBrace => open += 1,
+                TokenType::RightParen | TokenType::RightBrace if open == 0 => return,
+                TokenType::RightParen | TokenType::RightBrace => open -= 1,
+                _ => (),
+            }
+
+            let ends: bool = matches!(self.advance().token_type, TokenType::Semicolon | TokenType::RightBrace);
+            if ends && open == 0 && !self.check(TokenType::Else) { return; }
+        }
     }
 
     fn trial(&mut self, token_types: Vec<TokenType>) -> bool {
@@ -125,7 +502,7 @@ impl<'a> Parser<'a> {
         self.peek().token_type == token_type 
     }
 
-    fn advance(&mut self) -> Token {
+    fn advance(&mut self) -> &Token {
         if !self.is_at_end() { self.current += 1 }
         self.previous()
     }
@@ -134,21 +511,21 @@ impl<'a> Parser<'a> {
         self.peek().token_type == TokenType::Eof
     }
 
-    fn peek(&self) -> Token {
-        self.tokens[self.current].clone()
+    fn peek(&self) -> &Token {
+        &self.tokens[self.current]
     }
 
-    fn previous(&self) -> Token {
-        self.tokens[self.current - 1].clone()
+    fn previous(&self) -> &Token {
+        &self.tokens[self.current - 1]
     }
 
-    fn error(&mut self, token: Token, message: String) -> Error {
-        self.lox.parse_error(token, message);
+    fn error(&mut self, code: Code, token: Token, message: String) -> Error {
+        self.diagnostics.push(Diagnostic::at_token(code, &token, message));
         Error::Parser
     }
 
     fn synchronize(&mut self) {
-        let mut _tmp = self.advance();
+        self.advance();
 
         while !self.is_at_end() {
             if self.previous().token_type == TokenType::Semicolon { return; }
@@ -156,8 +533,77 @@ impl<'a> Parser<'a> {
             match self.peek().token_type {
                 TokenType::Class | TokenType::Fun | TokenType::Var | TokenType::For | 
                     TokenType::If | TokenType::While | TokenType::Print | TokenType::Return => { return; },
-                _ => { _tmp = self.advance() },
+                _ => { self.advance(); },
             }
         }
     }
 }
+
+/// The text of a string segment token, without its quotes or `${`.
+fn segment(token: &Token) -> String {
+    match &token.literal {
+        Literal::String(value) => value.clone(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::Token;
+    use crate::stmt::Stmt;
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::diagnostic::Code;
+
+    #[test]
+    fn test_reports_every_error_and_keeps_parsing() {
+        let source: &str = "
+            var = 1;
+            print (1 + ;
+            var ok = 2;
+            fun (a) {}
+            print 1 +* 2;
+            while (ok print ok;
+            print ok;
+        ";
+
+        let tokens: Vec<Token> = Scanner::new(String::from(source)).scan_tokens();
+        let mut parser: Parser = Parser::new(tokens);
+        let statements: Vec<Stmt> = parser.parse();
+
+        let lines: Vec<usize> = parser.diagnostics().iter().map(|diagnostic| diagnostic.span.line).collect();
+        assert_eq!(lines, vec![2, 3, 5, 6, 7]);
+        assert_eq!(parser.diagnostics()[1].code, Code::ExpectedExpression);
+
+        // `var ok = 2;` and the trailing `print ok;` survive recovery.
+        assert_eq!(statements.len(), 2);
+        assert!(matches!(statements[0], Stmt::Var { .. }));
+        assert!(matches!(statements[1], Stmt::Print { .. }));
+    }
+
+    #[test]
+    fn test_unexpected_token_does_not_panic() {
+        let tokens: Vec<Token> = Scanner::new(String::from(") ( ; print")).scan_tokens();
+        let mut parser: Parser = Parser::new(tokens);
+
+        assert!(parser.parse().is_empty());
+        assert_eq!(parser.diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_empty_interpolation_expects_an_expression() {
+        let tokens: Vec<Token> = Scanner::new(String::from("print \"a${}b\";")).scan_tokens();
+        let mut parser: Parser = Parser::new(tokens);
+        parser.parse();
+
+        assert_eq!(parser.diagnostics().len(), 1);
+        assert_eq!(parser.diagnostics()[0].code, Code::ExpectedExpression);
+        assert_eq!(parser.diagnostics()[0].span.column, 11);
+
+        // A nested string right after `${` is still an expression.
+        let tokens: Vec<Token> = Scanner::new(String::from("print \"${\"x\"}${\"${1}\"}\";")).scan_tokens();
+        let mut parser: Parser = Parser::new(tokens);
+        parser.parse();
+        assert!(parser.diagnostics().is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use std::io::IsTerminal;
+
+use crate::diagnostic::{Diagnostic, Severity, Span};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Renders diagnostics rustc-style: a header, the `file:line:column` location,
+/// the offending source line and a `^^^` underline beneath the exact lexeme.
+pub struct Renderer<'a> {
+    file_name: &'a str,
+    source: &'a str,
+    color: bool,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(file_name: &'a str, source: &'a str) -> Self {
+        Self {
+            file_name,
+            source,
+            color: false,
+        }
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Colour is used when stderr is a terminal and `NO_COLOR` is unset.
+    pub fn stderr_supports_color() -> bool {
+        std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let severity_color: &str = match diagnostic.severity {
+            Severity::Error => RED,
+            Severity::Warning => YELLOW,
+            Severity::Note => BLUE,
+        };
+
+        let mut out: String = format!(
+            "{}: {}\n",
+            self.paint(severity_color, &format!("{}[{}]", diagnostic.severity, diagnostic.code)),
+            self.paint(BOLD, &diagnostic.message),
+        );
+
+        let primary: Option<Snippet> = self.snippet(&diagnostic.span);
+        let secondary: Vec<(Snippet, &str)> = diagnostic.labels.iter()
+            .filter_map(|label| self.snippet(&label.span).map(|snippet| (snippet, label.message.as_str())))
+            .collect();
+
+        let gutter: usize = std::iter::once(diagnostic.span.line)
+            .chain(diagnostic.labels.iter().map(|label| label.span.line))
+            .max()
+            .unwrap_or(0)
+            .to_string()
+            .len();
+        let blank: String = " ".repeat(gutter);
+
+        match &primary {
+            Some(snippet) => out.push_str(&format!("{}{} {}:{}:{}\n", blank, self.paint(BLUE, "-->"), self.file_name, snippet.line, snippet.column)),
+            None => out.push_str(&format!("{}{} {}:{}\n", blank, self.paint(BLUE, "-->"), self.file_name, diagnostic.span.line)),
+        }
+
+        if primary.is_some() || !secondary.is_empty() {
+            out.push_str(&format!("{} {}\n", blank, self.paint(BLUE, "|")));
+        }
+
+        if let Some(snippet) = &primary {
+            let label: &str = diagnostic.location.as_deref().unwrap_or("");
+            out.push_str(&self.render_snippet(snippet, gutter, '^', severity_color, label));
+        }
+
+        for (snippet, message) in &secondary {
+            out.push_str(&self.render_snippet(snippet, gutter, '-', BLUE, message));
+        }
+
+        for note in &diagnostic.notes {
+            out.push_str(&format!("{} {} note: {}\n", blank, self.paint(BLUE, "="), note));
+        }
+
+        out
+    }
+
+    fn render_snippet(&self, snippet: &Snippet, gutter: usize, marker: char, color: &str, label: &str) -> String {
+        let line_number: String = format!("{:>width$}", snippet.line, width = gutter);
+        let marks: String = marker.to_string().repeat(snippet.width);
+
+        let mut underline: String = format!("{}{}", snippet.padding, self.paint(color, &marks));
+        if !label.is_empty() {
+            underline.push(' ');
+            underline.push_str(&self.paint(color, label));
+        }
+
+        format!(
+            "{} {} {}\n{} {} {}\n",
+            self.paint(BLUE, &line_number), self.paint(BLUE, "|"), snippet.text,
+            " ".repeat(gutter), self.paint(BLUE, "|"), underline,
+        )
+    }
+
+    /// Locates `span` in the source. Spans without a known column (synthetic
+    /// tokens) have no snippet.
+    fn snippet(&self, span: &Span) -> Option<Snippet<'a>> {
+        if span.column == 0 || span.start > self.source.len() { return None; }
+        if !self.source.is_char_boundary(span.start) { return None; }
+
+        let line_start: usize = self.source[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end: usize = self.source[span.start..].find('\n').map(|i| span.start + i).unwrap_or(self.source.len());
+        let text: &str = self.source[line_start..line_end].trim_end_matches('\r');
+
+        // Reuse tabs from the source line so the underline stays aligned.
+        let padding: String = self.source[line_start..span.start].chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let end: usize = span.end.clamp(span.start, line_end);
+        let width: usize = match self.source.get(span.start..end) {
+            Some(lexeme) => lexeme.chars().count().max(1),
+            None => 1,
+        };
+
+        Some(Snippet {
+            line: span.line,
+            column: span.column,
+            text,
+            padding,
+            width,
+        })
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        match self.color {
+            true => format!("{}{}{}", color, text, RESET),
+            false => String::from(text),
+        }
+    }
+}
+
+struct Snippet<'a> {
+    line: usize,
+    column: usize,
+    text: &'a str,
+    padding: String,
+    width: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Lox;
+    use crate::render::Renderer;
+
+    fn render_first(source: &str) -> String {
+        let mut lox: Lox = Lox::new();
+        lox.run(String::from(source)).unwrap();
+        Renderer::new("test.lox", source).render(&lox.diagnostics()[0])
+    }
+
+    #[test]
+    fn test_underlines_lexeme() {
+        let rendered: String = render_first("var x = 1;\nprint x - \"two\";");
+
+        assert_eq!(rendered, concat!(
+            "error[E0300]: Operands must be numbers.\n",
+            " --> test.lox:2:9\n",
+            "  |\n",
+            "2 | print x - \"two\";\n",
+            "  |         ^\n",
+        ));
+    }
+
+    #[test]
+    fn test_secondary_label() {
+        let rendered: String = render_first("{\n  var a = 1;\n  var a = 2;\n}");
+
+        assert_eq!(rendered, concat!(
+            "error[E0201]: Already a variable with this name in this scope.\n",
+            " --> test.lox:3:7\n",
+            "  |\n",
+            "3 |   var a = 2;\n",
+            "  |       ^ at 'a'\n",
+            "2 |   var a = 1;\n",
+            "  |       - previous declaration here\n",
+        ));
+    }
+
+    #[test]
+    fn test_color_wraps_in_escape_codes() {
+        let mut lox: Lox = Lox::new();
+        lox.run(String::from("@")).unwrap();
+
+        let rendered: String = Renderer::new("test.lox", "@").with_color(true).render(&lox.diagnostics()[0]);
+        assert!(rendered.starts_with("\x1b[1;31merror[E0001]\x1b[0m"));
+    }
+}
@@ -0,0 +1,305 @@
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+use crate::Lox;
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::expr::Expr;
+use crate::stmt::Stmt;
+use crate::scanner::Scanner;
+use crate::parser::Parser;
+use crate::interpreter::Interpreter;
+use crate::value::Value;
+use crate::ast_printer::{AstPrinter, Printer};
+use crate::diagnostic::{Code, Diagnostic};
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = "... ";
+/// Kept in the home directory, so history carries over between sessions.
+const HISTORY_FILE: &str = ".rlox_history";
+
+const HELP: &str = "\
+Commands:
+    :tokens <code>   Print the tokens of <code>
+    :ast <code>      Print the syntax tree of <code>
+    :time <code>     Run <code> and print how long it took
+    :load <file>     Run a script in this session
+    :env             List the global variables and their values
+    :reset           Forget every global variable defined so far
+    :help            Print this help
+
+Anything else is run as Lox. An expression entered without its `;` prints
+its value, and entries continue over several lines until they are complete.
+";
+
+/// What an entry parsed as: ordinary statements, or an expression written
+/// without its `;`, whose value gets printed.
+enum Entry {
+    Statements(Vec<Stmt>),
+    Expression(Box<Expr>),
+}
+
+impl Lox {
+    /// Reads entries with line editing until end of input, running each one
+    /// once [`is_complete`] says so. Ctrl-C throws away the entry being typed.
+    pub(crate) fn run_prompt(&mut self) -> io::Result<()> {
+        let mut editor: DefaultEditor = DefaultEditor::new().map_err(readline_error)?;
+        let history: Option<PathBuf> = history_path();
+        if let Some(path) = &history {
+            let _ = editor.load_history(path);
+        }
+
+        // Saved however the session ends, even when an entry fails to write.
+        let result: io::Result<()> = self.read_entries(&mut editor);
+        if let Some(path) = &history {
+            let _ = editor.save_history(path);
+        }
+        result
+    }
+
+    /// The loop of [`Lox::run_prompt`], which adds every entry to the
+    /// editor's history.
+    fn read_entries(&mut self, editor: &mut DefaultEditor) -> io::Result<()> {
+        let mut entry: String = String::new();
+        loop {
+            let prompt: &str = match entry.is_empty() {
+                true => PROMPT,
+                false => CONTINUATION_PROMPT,
+            };
+
+            match editor.readline(prompt) {
+                Ok(line) if entry.is_empty() && line.trim().is_empty() => continue,
+                Ok(line) if entry.is_empty() && line.trim_start().starts_with(':') => {
+                    let _ = editor.add_history_entry(line.trim());
+                    self.run_command(line.trim())?;
+                },
+                Ok(line) => {
+                    entry.push_str(&line);
+                    entry.push('\n');
+                    if !is_complete(&entry) { continue; }
+
+                    let _ = editor.add_history_entry(entry.trim_end());
+                    self.run_entry(std::mem::take(&mut entry))?;
+                },
+                Err(ReadlineError::Interrupted) => entry.clear(),
+                Err(ReadlineError::Eof) => break,
+                Err(error) => return Err(readline_error(error)),
+            }
+        }
+
+        // Whatever was left unfinished still gets its errors reported.
+        match entry.is_empty() {
+            true => Ok(()),
+            false => self.run_entry(entry),
+        }
+    }
+
+    /// Runs one of the `:` commands listed in [`HELP`].
+    fn run_command(&mut self, command: &str) -> io::Result<()> {
+        let (name, argument) = match command.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, ""),
+        };
+
+        match (name, argument.is_empty()) {
+            (":help", _) => write!(io::stdout(), "{}", HELP)?,
+            (":env", _) => {
+                let mut stdout: io::StdoutLock = io::stdout().lock();
+                for (name, value) in self.interpreter.globals() {
+                    match value {
+                        Value::String(value) => writeln!(stdout, "{} = {:?}", name, value)?,
+                        value => writeln!(stdout, "{} = {}", name, value)?,
+                    }
+                }
+            },
+            (":reset", _) => self.interpreter = Interpreter::new(),
+            (":tokens", false) => self.print_entry_tokens(argument)?,
+            (":ast", false) => self.print_entry_ast(argument)?,
+            (":time", false) => {
+                let start: Instant = Instant::now();
+                self.run_entry(String::from(argument))?;
+                writeln!(io::stdout(), "Took {:?}.", start.elapsed())?;
+            },
+            (":load", false) => self.load(argument)?,
+            (":tokens" | ":ast" | ":time", true) => eprintln!("Usage: {} <code>", name),
+            (":load", true) => eprintln!("Usage: :load <file>"),
+            _ => eprintln!("Unknown command '{}'. Type :help to list the commands.", name),
+        }
+        Ok(())
+    }
+
+    /// Runs one entry like [`Lox::eval`]. The diagnostics are printed and
+    /// cleared, ready for the next entry.
+    fn run_entry(&mut self, source: String) -> io::Result<()> {
+        let result: io::Result<()> = self.eval(source);
+
+        self.print_diagnostics();
+        self.diagnostics.clear();
+        result
+    }
+
+    /// Runs `source` like [`Lox::run`], except that an expression without its
+    /// `;` prints its value.
+    pub(crate) fn eval(&mut self, source: String) -> io::Result<()> {
+        self.source = source;
+        let statements: Vec<Stmt> = match self.parse_entry() {
+            Entry::Statements(statements) => statements,
+            Entry::Expression(expression) => vec![Stmt::Print { expression: *expression }],
+        };
+        self.execute(&statements)
+    }
+
+    /// Parses [`Lox::source`] as an entry, which may be an expression
+    /// without its `;`.
+    fn parse_entry(&mut self) -> Entry {
+        let statements: Vec<Stmt> = self.parse();
+        if !self.had_error() { return Entry::Statements(statements); }
+
+        match expression(&self.source) {
+            Some(expression) => {
+                self.diagnostics.clear();
+                Entry::Expression(Box::new(expression))
+            },
+            None => Entry::Statements(statements),
+        }
+    }
+
+    fn print_entry_tokens(&mut self, source: &str) -> io::Result<()> {
+        self.source = String::from(source);
+        let mut scanner: Scanner = Scanner::new(self.source.clone());
+        let tokens: Vec<Token> = scanner.scan_tokens();
+
+        let mut stdout: io::StdoutLock = io::stdout().lock();
+        for token in tokens {
+            writeln!(stdout, "{}:{} {}", token.line, token.column, token)?;
+        }
+        drop(stdout);
+
+        self.diagnostics = scanner.take_diagnostics();
+        self.print_diagnostics();
+        self.diagnostics.clear();
+        Ok(())
+    }
+
+    fn print_entry_ast(&mut self, source: &str) -> io::Result<()> {
+        self.source = String::from(source);
+        let statements: Vec<Stmt> = match self.parse_entry() {
+            Entry::Statements(statements) => statements,
+            Entry::Expression(expression) => vec![Stmt::Expression { expression: *expression }],
+        };
+
+        if !self.had_error() {
+            if let Ok(output) = AstPrinter.print_program(&statements) {
+                write!(io::stdout(), "{}", output)?;
+            }
+        }
+        self.print_diagnostics();
+        self.diagnostics.clear();
+        Ok(())
+    }
+
+    /// Runs a script in the session, so that its globals stay defined.
+    fn load(&mut self, file_path: &str) -> io::Result<()> {
+        if let Err(error) = self.read_input(file_path) {
+            eprintln!("rlox: {}", error);
+            return Ok(());
+        }
+
+        let statements: Vec<Stmt> = self.parse();
+        let result: io::Result<()> = self.execute(&statements);
+
+        self.print_diagnostics();
+        self.diagnostics.clear();
+        self.file_name = String::from("<stdin>");
+        result
+    }
+}
+
+/// Whether `source` is a whole entry, rather than one that needs more lines
+/// because a bracket, string or comment is still open or a statement stops
+/// partway through.
+pub fn is_complete(source: &str) -> bool {
+    let mut scanner: Scanner = Scanner::new(String::from(source));
+    let tokens: Vec<Token> = scanner.scan_tokens();
+
+    let unterminated: bool = scanner.diagnostics().iter()
+        .any(|diagnostic| matches!(diagnostic.code, Code::UnterminatedString | Code::UnterminatedComment));
+    let depth: isize = tokens.iter()
+        .map(|token| match token.token_type {
+            TokenType::LeftParen | TokenType::LeftBrace => 1,
+            TokenType::RightParen | TokenType::RightBrace => -1,
+            _ => 0,
+        })
+        .sum();
+    if unterminated || depth > 0 { return false; }
+
+    let mut parser: Parser = Parser::new(tokens);
+    parser.parse();
+    let stops_early: bool = parser.diagnostics().iter()
+        .any(|diagnostic| diagnostic.is_error() && diagnostic.location.as_deref() == Some("at end"));
+
+    !stops_early || expression(source).is_some()
+}
+
+/// The expression `source` consists of, if it is one written without its `;`.
+fn expression(source: &str) -> Option<Expr> {
+    // On a line of its own, so that a trailing comment can't swallow it.
+    let mut scanner: Scanner = Scanner::new(format!("{}\n;", source));
+    let tokens: Vec<Token> = scanner.scan_tokens();
+    let mut parser: Parser = Parser::new(tokens);
+    let statements: Vec<Stmt> = parser.parse();
+
+    let had_error: bool = scanner.diagnostics().iter().chain(parser.diagnostics()).any(Diagnostic::is_error);
+    match <[Stmt; 1]>::try_from(statements) {
+        Ok([Stmt::Expression { expression }]) if !had_error => Some(expression),
+        _ => None,
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+fn readline_error(error: ReadlineError) -> io::Error {
+    match error {
+        ReadlineError::Io(error) => error,
+        error => io::Error::other(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::is_complete;
+
+    #[test]
+    fn test_open_brackets_strings_and_comments_continue() {
+        assert!(!is_complete("fun f() {\n"));
+        assert!(!is_complete("print (1 +\n"));
+        assert!(!is_complete("print \"two\nlines\n"));
+        assert!(!is_complete("/* still\n"));
+        assert!(is_complete("fun f() {\n    return 1;\n}\n"));
+    }
+
+    #[test]
+    fn test_statements_that_stop_early_continue() {
+        assert!(!is_complete("var a =\n"));
+        assert!(!is_complete("print 1\n"));
+        assert!(!is_complete("if (a)\n"));
+        assert!(is_complete("var a = 1;\n"));
+    }
+
+    #[test]
+    fn test_bare_expressions_and_errors_are_complete() {
+        assert!(is_complete("1 + 2\n"));
+        assert!(is_complete("f() // call it\n"));
+        assert!(is_complete("}\n"));
+        assert!(is_complete("print +;\n"));
+    }
+}
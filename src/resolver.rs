@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::interpreter::Interpreter;
+use crate::error::Error;
+use crate::diagnostic::{Code, Diagnostic, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+}
+
+/// A name declared in a local scope. `defined` flips once its initializer has been resolved.
+struct Binding {
+    declaration: Token,
+    defined: bool,
+}
+
+/// Static pass run between parsing and interpretation. Binds every local
+/// variable reference to the number of scopes between it and its declaration.
+pub struct Resolver<'a> {
+    interpreter: &'a mut Interpreter,
+    diagnostics: Vec<Diagnostic>,
+    scopes: Vec<HashMap<String, Binding>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(interpreter: &'a mut Interpreter) -> Self {
+        Self {
+            interpreter,
+            diagnostics: vec![],
+            scopes: vec![],
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            let _ = self.resolve_stmt(statement);
+        }
+    }
+
+    /// Static errors found so far, in the order they were found.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) -> Result<(), Error> {
+        stmt.accept(self)
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) -> Result<(), Error> {
+        expr.accept(self)
+    }
+
+    fn resolve_function(&mut self, params: &[Token], body: &[Stmt], function_type: FunctionType) -> Result<(), Error> {
+        let enclosing_function: FunctionType = self.current_function;
+        self.current_function = function_type;
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve(body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+        Ok(())
+    }
+
+    fn resolve_local(&mut self, id: usize, name: &Token) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(&name.lexeme) {
+                self.interpreter.resolve(id, depth);
+                return;
+            }
+        }
+    }
+
+    fn error(&mut self, code: Code, token: &Token, message: String) {
+        self.diagnostics.push(Diagnostic::at_token(code, token, message));
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else { return; };
+
+        if let Some(previous) = scope.get(&name.lexeme) {
+            let diagnostic: Diagnostic = Diagnostic::at_token(Code::AlreadyDeclared, name, String::from("Already a variable with this name in this scope."))
+                .with_label(Span::from(&previous.declaration), String::from("previous declaration here"));
+            self.diagnostics.push(diagnostic);
+        }
+
+        scope.insert(name.lexeme.clone(), Binding { declaration: name.clone(), defined: false });
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), Binding { declaration: name.clone(), defined: true });
+        }
+    }
+
+    /// Binds an implicit name such as `this` or `super` in the innermost scope.
+    fn define_implicit(&mut self, name: &str, declaration: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(String::from(name), Binding { declaration: declaration.clone(), defined: true });
+        }
+    }
+}
+
+impl Visitor<()> for Resolver<'_> {
+    fn visit_assign_expr(&mut self, id: usize, name: &Token, value: &Expr) -> Result<(), Error> {
+        self.resolve_expr(value)?;
+        self.resolve_local(id, name);
+        Ok(())
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, _operator: &Token, right: &Expr) -> Result<(), Error> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<(), Error> {
+        self.resolve_expr(callee)?;
+        for argument in arguments {
+            self.resolve_expr(argument)?;
+        }
+        Ok(())
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, _name: &Token) -> Result<(), Error> {
+        self.resolve_expr(object)
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr) -> Result<(), Error> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_interpolation_expr(&mut self, _segments: &[String], expressions: &[Expr]) -> Result<(), Error> {
+        expressions.iter().try_for_each(|expression| self.resolve_expr(expression))
+    }
+
+    fn visit_literal_expr(&mut self, _literal: &Literal) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, _operator: &Token, right: &Expr) -> Result<(), Error> {
+        self.resolve_expr(left)?;
+        self.resolve_expr(right)
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, _name: &Token, value: &Expr) -> Result<(), Error> {
+        self.resolve_expr(value)?;
+        self.resolve_expr(object)
+    }
+
+    fn visit_super_expr(&mut self, id: usize, keyword: &Token, _method: &Token) -> Result<(), Error> {
+        match self.current_class {
+            ClassType::None => self.error(Code::InvalidSuper, keyword, String::from("Can't use 'super' outside of a class.")),
+            ClassType::Class => self.error(Code::InvalidSuper, keyword, String::from("Can't use 'super' in a class with no superclass.")),
+            ClassType::Subclass => (),
+        }
+
+        self.resolve_local(id, keyword);
+        Ok(())
+    }
+
+    fn visit_this_expr(&mut self, id: usize, keyword: &Token) -> Result<(), Error> {
+        if self.current_class == ClassType::None {
+            self.error(Code::ThisOutsideClass, keyword, String::from("Can't use 'this' outside of a class."));
+            return Ok(());
+        }
+
+        self.resolve_local(id, keyword);
+        Ok(())
+    }
+
+    fn visit_unary_expr(&mut self, _operator: &Token, right: &Expr) -> Result<(), Error> {
+        self.resolve_expr(right)
+    }
+
+    fn visit_variable_expr(&mut self, id: usize, name: &Token) -> Result<(), Error> {
+        if let Some(binding) = self.scopes.last().and_then(|scope| scope.get(&name.lexeme)) {
+            if !binding.defined {
+                let diagnostic: Diagnostic = Diagnostic::at_token(Code::OwnInitializer, name, String::from("Can't read local variable in its own initializer."))
+                    .with_label(Span::from(&binding.declaration), String::from("variable declared here"));
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        self.resolve_local(id, name);
+        Ok(())
+    }
+}
+
+impl StmtVisitor<()> for Resolver<'_> {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<(), Error> {
+        self.begin_scope();
+        self.resolve(statements);
+        self.end_scope();
+        Ok(())
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<(), Error> {
+        let enclosing_class: ClassType = self.current_class;
+        self.current_class = ClassType::Class;
+
+        self.declare(name);
+        self.define(name);
+
+        if let Some(superclass) = superclass {
+            if let Expr::Variable { name: superclass_name, .. } = superclass {
+                if superclass_name.lexeme == name.lexeme {
+                    self.error(Code::InheritsFromSelf, superclass_name, String::from("A class can't inherit from itself."));
+                }
+            }
+
+            self.current_class = ClassType::Subclass;
+            self.resolve_expr(superclass)?;
+
+            self.begin_scope();
+            self.define_implicit("super", name);
+        }
+
+        self.begin_scope();
+        self.define_implicit("this", name);
+
+        for method in methods {
+            if let Stmt::Function { name, params, body } = method {
+                let function_type: FunctionType = match name.lexeme == "init" {
+                    true => FunctionType::Initializer,
+                    false => FunctionType::Method,
+                };
+                self.resolve_function(params, body, function_type)?;
+            }
+        }
+
+        self.end_scope();
+        if superclass.is_some() { self.end_scope(); }
+
+        self.current_class = enclosing_class;
+        Ok(())
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<(), Error> {
+        // Defined eagerly so the function can refer to itself recursively.
+        self.declare(name);
+        self.define(name);
+
+        self.resolve_function(params, body, FunctionType::Function)
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<(), Error> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(then_branch)?;
+        if let Some(else_branch) = else_branch {
+            self.resolve_stmt(else_branch)?;
+        }
+        Ok(())
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<(), Error> {
+        self.resolve_expr(expression)
+    }
+
+    fn visit_return_stmt(&mut self, keyword: &Token, value: Option<&Expr>) -> Result<(), Error> {
+        if self.current_function == FunctionType::None {
+            self.error(Code::TopLevelReturn, keyword, String::from("Can't return from top-level code."));
+        }
+
+        match value {
+            Some(_) if self.current_function == FunctionType::Initializer => {
+                self.error(Code::InitializerReturn, keyword, String::from("Can't return a value from an initializer."));
+                Ok(())
+            },
+            Some(value) => self.resolve_expr(value),
+            None => Ok(()),
+        }
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<(), Error> {
+        self.declare(name);
+        if let Some(initializer) = initializer {
+            self.resolve_expr(initializer)?;
+        }
+        self.define(name);
+        Ok(())
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<(), Error> {
+        self.resolve_expr(condition)?;
+        self.resolve_stmt(body)
+    }
+}
@@ -0,0 +1,204 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::error::Error;
+use crate::ast_printer::Printer;
+
+/// Prints reverse Polish notation: operands first, then the operator, so
+/// `(1 + 2) * 3` becomes `1 2 + 3 *`. Groupings need no parentheses and unary
+/// minus is written `neg` to tell it apart from subtraction.
+///
+/// Statements follow PostScript: bodies are `{ }` procedures, as in
+/// `cond { then } { else } ifelse` or `{ cond } { body } while`.
+pub struct RpnPrinter;
+
+impl RpnPrinter {
+    pub fn print(&mut self, expr: &Expr) -> Result<String, Error> {
+        expr.accept(self)
+    }
+
+    fn postfix(&mut self, operands: Vec<&Expr>, operator: &str) -> Result<String, Error> {
+        let mut result: String = String::new();
+        for operand in operands {
+            result.push_str(&operand.accept(self)?);
+            result.push(' ');
+        }
+        result.push_str(operator);
+        Ok(result)
+    }
+
+    /// A statement as a `{ }` procedure, unless it is already a block.
+    fn procedure(&mut self, statement: &Stmt) -> Result<String, Error> {
+        match statement {
+            Stmt::Block { .. } => statement.accept(self),
+            _ => Ok(format!("{{ {} }}", statement.accept(self)?)),
+        }
+    }
+
+    fn procedure_of(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = String::from("{");
+        for statement in statements {
+            result.push(' ');
+            result.push_str(&statement.accept(self)?);
+        }
+        result.push_str(" }");
+        Ok(result)
+    }
+}
+
+impl Visitor<String> for RpnPrinter {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        self.postfix(vec![value], &format!("{} =", name.lexeme))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        self.postfix(vec![left, right], &operator.lexeme)
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        let mut operands: Vec<&Expr> = vec![callee];
+        operands.extend(arguments);
+        self.postfix(operands, &format!("call/{}", arguments.len()))
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<String, Error> {
+        self.postfix(vec![object], &format!(".{}", name.lexeme))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
+        expression.accept(self)
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let mut result: String = format!("{:?}", segments[0]);
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            result.push_str(&format!(" {} {:?}", expression.accept(self)?, segment));
+        }
+        result.push_str(&format!(" interpolate/{}", segments.len() + expressions.len()));
+        Ok(result)
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
+        match literal {
+            Literal::String(value) => Ok(format!("{:?}", value)),
+            Literal::Empty => Ok(String::from("nil")),
+            _ => Ok(literal.to_string()),
+        }
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        self.postfix(vec![left, right], &operator.lexeme)
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        self.postfix(vec![object, value], &format!(".{} =", name.lexeme))
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(format!("super .{}", method.lexeme))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<String, Error> {
+        Ok(String::from("this"))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
+        let operator: &str = match operator.token_type {
+            TokenType::Minus => "neg",
+            _ => &operator.lexeme,
+        };
+        self.postfix(vec![right], operator)
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(name.lexeme.clone())
+    }
+}
+
+impl StmtVisitor<String> for RpnPrinter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        self.procedure_of(statements)
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        let superclass: String = match superclass {
+            Some(superclass) => format!("{} < ", superclass.accept(self)?),
+            None => String::new(),
+        };
+        Ok(format!("{} {}{} class", name.lexeme, superclass, self.procedure_of(methods)?))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        self.postfix(vec![expression], ";")
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        Ok(format!("{} [{}] {} fun", name.lexeme, params.join(" "), self.procedure_of(body)?))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let condition: String = condition.accept(self)?;
+        let then_branch: String = self.procedure(then_branch)?;
+        match else_branch {
+            Some(else_branch) => Ok(format!("{} {} {} ifelse", condition, then_branch, self.procedure(else_branch)?)),
+            None => Ok(format!("{} {} if", condition, then_branch)),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        self.postfix(vec![expression], "print")
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        self.postfix(value.into_iter().collect(), "return")
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        self.postfix(initializer.into_iter().collect(), &format!("{} var", name.lexeme))
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        Ok(format!("{{ {} }} {} while", condition.accept(self)?, self.procedure(body)?))
+    }
+}
+
+impl Printer for RpnPrinter {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = String::new();
+        for statement in statements {
+            result.push_str(&statement.accept(self)?);
+            result.push('\n');
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::stmt::Stmt;
+    use crate::ast_printer::Printer;
+    use crate::rpn_printer::RpnPrinter;
+
+    fn print(source: &str) -> String {
+        let statements: Vec<Stmt> = Parser::new(Scanner::new(String::from(source)).scan_tokens()).parse();
+        RpnPrinter.print_program(&statements).unwrap()
+    }
+
+    #[test]
+    fn test_expressions_are_postfix() {
+        assert_eq!(print("(1 + 2) * -3 - f(a, \"b\").c;"), "1 2 + 3 neg * f a \"b\" call/2 .c - ;\n");
+    }
+
+    #[test]
+    fn test_statements_use_procedures() {
+        assert_eq!(print("if (x) print 1; else { y = 2; }"), "x { 1 print } { 2 y = ; } ifelse\n");
+        assert_eq!(print("while (i < 3) i = i + 1;"), "{ i 3 < } { i 1 + i = ; } while\n");
+    }
+}
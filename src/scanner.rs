@@ -1,22 +1,31 @@
 use std::collections::HashMap;
 
-use crate::token::Token;
+use crate::token::{Token, Trivia, TriviaKind};
 use crate::token_type::TokenType;
 use crate::literal::Literal;
-use crate::Lox;
+use crate::diagnostic::{Code, Diagnostic, Span};
 
-pub struct Scanner<'a> {
-    lox: &'a mut Lox,
+pub struct Scanner {
     source: String,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
+    line_start: usize,
+    start_line: usize,
+    start_column: usize,
     keywords: HashMap<String, TokenType>,
+    /// One entry per open `${`: where it opened and how many `{` are nested inside it.
+    interpolations: Vec<(Span, usize)>,
+    /// Whether whitespace, comments and skipped text are attached to tokens.
+    lossless: bool,
+    /// Trivia waiting for the next token, in lossless mode.
+    trivia: Vec<Trivia>,
+    diagnostics: Vec<Diagnostic>,
 }
 
-impl<'a> Scanner<'a> {
-    pub fn new(source: String, lox: &'a mut Lox) -> Self {
+impl Scanner {
+    pub fn new(source: String) -> Self {
         let mut keywords: HashMap<String, TokenType> = HashMap::new();
         keywords.insert(String::from("and"), TokenType::And);
         keywords.insert(String::from("class"), TokenType::Class);
@@ -36,34 +45,86 @@ impl<'a> Scanner<'a> {
         keywords.insert(String::from("while"), TokenType::While);
 
         Self {
-            lox,
             source,
             tokens: vec![],
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            start_line: 1,
+            start_column: 1,
             keywords,
+            interpolations: vec![],
+            lossless: false,
+            trivia: vec![],
+            diagnostics: vec![],
         }
     }
 
+    /// In lossless mode every token carries its leading and trailing trivia,
+    /// so the source can be rebuilt byte-for-byte with `Token::full_text`.
+    pub fn with_trivia(mut self, lossless: bool) -> Self {
+        self.lossless = lossless;
+        self
+    }
+
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         while !self.is_at_end() {
             //starting next lexme
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.column();
+
+            let tokens: usize = self.tokens.len();
             self.scan_token();
+
+            if self.lossless && self.tokens.len() == tokens {
+                self.add_trivia();
+            }
         }
 
-        self.tokens.push(Token::from(TokenType::Eof, String::new(), Literal::Empty, self.line.clone()));
+        while let Some((opened, _)) = self.interpolations.pop() {
+            self.diagnostics.push(Diagnostic::error(Code::UnterminatedString, String::from("Unterminated string interpolation."), opened));
+        }
+
+        let column: usize = self.column();
+        let end: usize = self.source.len();
+        self.add_token_at_end(column, end);
         self.tokens.clone()
     }
 
+    /// Problems found while scanning, in source order.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+
     fn scan_token(&mut self) {
         let c: char = self.advance();
         match c {
             '(' => self.add_token_helper(TokenType::LeftParen),
             ')' => self.add_token_helper(TokenType::RightParen),
-            '{' => self.add_token_helper(TokenType::LeftBrace),
-            '}' => self.add_token_helper(TokenType::RightBrace),
+            '{' => {
+                if let Some((_, depth)) = self.interpolations.last_mut() { *depth += 1; }
+                self.add_token_helper(TokenType::LeftBrace);
+            },
+            '}' => {
+                match self.interpolations.last_mut() {
+                    // Closes a `${`: the rest of the string literal follows.
+                    Some((_, 0)) => {
+                        self.interpolations.pop();
+                        self.string();
+                    },
+                    Some((_, depth)) => {
+                        *depth -= 1;
+                        self.add_token_helper(TokenType::RightBrace);
+                    },
+                    None => self.add_token_helper(TokenType::RightBrace),
+                }
+            },
             ',' => self.add_token_helper(TokenType::Comma),
             '.' => self.add_token_helper(TokenType::Dot),
             '-' => self.add_token_helper(TokenType::Minus),
@@ -100,17 +161,13 @@ impl<'a> Scanner<'a> {
                         self.advance();
                     }
                 } else if self.check_next_char('*') {
-                    while ( self.peek() != '*' || self.peek_next() != '/' ) && !self.is_at_end() {
-                        if self.peek() == '\n' { self.line += 1; }
-                        self.advance();
-                    }
-                    if !self.is_at_end() { self.current += 2; }
+                    self.block_comment();
                 } else {
                     self.add_token_helper(TokenType::Slash);
                 }
             },
             ' ' | '\r' | '\t' => (),
-            '\n' => self.line += 1,
+            '\n' => self.new_line(),
             '"' => self.string(),
             _ => {
                 if self.is_digit(c) {
@@ -118,12 +175,42 @@ impl<'a> Scanner<'a> {
                 } else if self.is_alpha(c) {
                     self.identifier();
                 } else {
-                    self.lox.error(self.line, String::from("Unexpected character"));
+                    self.error(Code::UnexpectedCharacter, String::from("Unexpected character."));
                 }
             },
         }
     }
 
+    /// Skips a `/* ... */` comment, which may contain further nested block
+    /// comments. The opening `/*` has already been consumed.
+    fn block_comment(&mut self) {
+        let opening: Span = Span::new(self.start_line, self.start_column, self.start, self.current);
+        let mut open: Vec<Span> = vec![opening];
+
+        while !open.is_empty() && !self.is_at_end() {
+            if self.peek() == '/' && self.peek_next() == '*' {
+                let start: usize = self.current;
+                self.current += 2;
+                open.push(self.span_from(start));
+            } else if self.peek() == '*' && self.peek_next() == '/' {
+                self.current += 2;
+                open.pop();
+            } else if self.advance() == '\n' {
+                self.new_line();
+            }
+        }
+
+        if open.is_empty() { return; }
+
+        let mut diagnostic: Diagnostic = Diagnostic::error(Code::UnterminatedComment, String::from("Unterminated block comment."), opening);
+        if open.len() > 1 {
+            diagnostic = diagnostic
+                .with_label(open[open.len() - 1], String::from("nested comment opened here"))
+                .with_note(format!("{} comments are still open; each '/*' needs its own '*/'.", open.len()));
+        }
+        self.diagnostics.push(diagnostic);
+    }
+
     fn identifier(&mut self) {
         while self.is_alpha_numeric(self.peek()) { self.advance(); };
         
@@ -135,83 +222,236 @@ impl<'a> Scanner<'a> {
         }
     }
 
+    /// Scans decimal (`12`, `1.5`, `2.5E3`, `1e-9`), hexadecimal (`0x1F`) and
+    /// binary (`0b1010`) literals. Digits may be grouped with '_' separators.
     fn number(&mut self) {
-        while self.is_digit(self.peek()) { self.advance(); }
+        let first: char = self.source[self.start..].chars().next().unwrap_or('0');
+
+        if first == '0' && matches!(self.peek(), 'x' | 'X') {
+            self.advance();
+            return self.radix_number(16, "hexadecimal", "0x");
+        }
+        if first == '0' && matches!(self.peek(), 'b' | 'B') {
+            self.advance();
+            return self.radix_number(2, "binary", "0b");
+        }
+
+        // The first digit has already been consumed.
+        self.digits(|c| c.is_ascii_digit());
 
         // Look for a fractional part.
         match self.peek() {
             '.' if self.is_digit(self.peek_next()) => {
                 self.advance();
 
-                while self.is_digit(self.peek()) { self.advance(); }
+                self.digits(|c| c.is_ascii_digit());
             }
             _ => ()
         }
-        
-        match self.source[self.start..self.current].parse::<f64>() {
+
+        // Look for an exponent.
+        if matches!(self.peek(), 'e' | 'E') {
+            self.advance();
+            if matches!(self.peek(), '+' | '-') { self.advance(); }
+
+            if self.digits(|c| c.is_ascii_digit()) == 0 {
+                self.error(Code::InvalidNumber, String::from("Expect digits in exponent."));
+                return;
+            }
+        }
+
+        if self.peek() == '_' {
+            while self.peek() == '_' || self.is_alpha_numeric(self.peek()) { self.advance(); }
+            self.error(Code::InvalidNumber, String::from("Digit separator '_' must be between digits."));
+            return;
+        }
+
+        let text: String = self.source[self.start..self.current].replace('_', "");
+        match text.parse::<f64>() {
             Ok(n) => self.add_token(TokenType::Number, Literal::Number(n)),
-            Err(_) => self.lox.error(self.line, String::from("DEBUG: Error parsing a string to a f32 in scanner.number function")),
+            Err(_) => self.error(Code::InvalidNumber, String::from("Invalid number literal.")),
         }
-        
     }
 
+    fn radix_number(&mut self, radix: u32, name: &str, prefix: &str) {
+        let digits_start: usize = self.current;
+        let count: usize = self.digits(|c| c.is_digit(radix));
+
+        if count == 0 {
+            while self.peek() == '_' { self.advance(); }
+            self.error(Code::InvalidNumber, format!("Expect {} digits after '{}'.", name, prefix));
+            return;
+        }
+
+        // Reject trailing junk such as `0b102` or `0x1_` rather than splitting it into more tokens.
+        if self.peek() == '_' || self.is_alpha_numeric(self.peek()) {
+            let bad: char = self.peek();
+            while self.peek() == '_' || self.is_alpha_numeric(self.peek()) { self.advance(); }
+
+            let message: String = match bad {
+                '_' => String::from("Digit separator '_' must be between digits."),
+                _ => format!("Invalid digit '{}' in {} literal.", bad, name),
+            };
+            self.error(Code::InvalidNumber, message);
+            return;
+        }
+
+        let digits: String = self.source[digits_start..self.current].replace('_', "");
+        match u64::from_str_radix(&digits, radix) {
+            Ok(n) => self.add_token(TokenType::Number, Literal::Number(n as f64)),
+            Err(_) => self.error(Code::InvalidNumber, format!("The {} literal is too large.", name)),
+        }
+    }
+
+    /// Consumes a run of digits accepted by `is_valid`, allowing single '_'
+    /// separators between them. Returns how many digits were consumed.
+    fn digits(&mut self, is_valid: impl Fn(char) -> bool) -> usize {
+        let mut count: usize = 0;
+
+        loop {
+            if is_valid(self.peek()) {
+                count += 1;
+            } else if !(self.peek() == '_' && is_valid(self.peek_next())) {
+                break;
+            }
+            self.advance();
+        }
+
+        count
+    }
+
+    /// Scans string contents up to the closing '"' or an opening `${`. The
+    /// latter emits an `Interpolation` token for the text so far; scanning of
+    /// the rest of the literal resumes at the matching '}'.
     fn string(&mut self) {
+        let mut value: String = String::new();
+
         while self.peek() != '"' && !self.is_at_end() {
-            match self.peek() {
-                '\n' => self.line += 1,
-                _ => {
-                    self.advance();
+            if self.peek() == '$' && self.peek_next() == '{' {
+                self.current += 2;
+                let opened: Span = self.span_from(self.current - 2);
+                self.interpolations.push((opened, 0));
+                self.add_token(TokenType::Interpolation, Literal::String(value));
+                return;
+            }
+
+            match self.advance() {
+                '\\' => {
+                    if let Some(c) = self.escape() { value.push(c); }
+                },
+                '\n' => {
+                    self.new_line();
+                    value.push('\n');
                 },
+                c => value.push(c),
             }
         }
 
         if self.is_at_end() {
-            self.lox.error(self.line, String::from("Unterminated string."));
+            self.error(Code::UnterminatedString, String::from("Unterminated string."));
             return;
         }
 
         // handling the closing '"'.
         self.advance();
 
-        // trimming the surrounding quotes.
-        let value: String = self.source[self.start + 1..self.current - 1].to_string();
         self.add_token(TokenType::String, Literal::String(value));
     }
 
+    /// Decodes the escape sequence after a '\\'. Reports and skips invalid ones.
+    fn escape(&mut self) -> Option<char> {
+        let escape_start: usize = self.current - 1;
+
+        let c: char = match self.peek() {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '"' => '"',
+            '\\' => '\\',
+            '$' => '$',
+            'u' => {
+                self.advance();
+                return self.unicode_escape(escape_start);
+            },
+            _ => {
+                // A newline is left for the string loop, which keeps the line count.
+                if self.peek() != '"' && self.peek() != '\n' && !self.is_at_end() { self.advance(); }
+                self.error_at(escape_start, Code::InvalidEscape, String::from("Invalid escape sequence."));
+                return None;
+            },
+        };
+
+        self.advance();
+        Some(c)
+    }
+
+    /// Decodes `{XXXX}` after `\\u`: one to six hex digits naming a Unicode scalar value.
+    fn unicode_escape(&mut self, escape_start: usize) -> Option<char> {
+        if !self.check_next_char('{') {
+            self.error_at(escape_start, Code::InvalidEscape, String::from("Expect '{' after '\\u'."));
+            return None;
+        }
+
+        let digits_start: usize = self.current;
+        while self.peek().is_ascii_hexdigit() { self.advance(); }
+        let digits: String = self.source[digits_start..self.current].to_string();
+
+        if !self.check_next_char('}') {
+            self.error_at(escape_start, Code::InvalidEscape, String::from("Expect '}' after unicode escape digits."));
+            return None;
+        }
+
+        let scalar: Option<char> = match digits.len() {
+            1..=6 => u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32),
+            _ => None,
+        };
+
+        if scalar.is_none() {
+            self.error_at(escape_start, Code::InvalidEscape, format!("Invalid unicode escape '\\u{{{}}}'.", digits));
+        }
+        scalar
+    }
+
     fn check_next_char(&mut self, expected: char) -> bool {
         if self.is_at_end() { return false; }
-        if self.source.as_bytes()[self.current] as char != expected { return false; }
+        if self.peek() != expected { return false; }
 
-        self.current += 1;
+        self.current += expected.len_utf8();
         true
     }
 
+    // `current` is always a byte offset on a char boundary; these decode the
+    // Unicode scalar value(s) that follow it.
     fn peek(&self) -> char {
-        match self.is_at_end() {
-            true => '\0',
-            false => self.source.as_bytes()[self.current] as char,
-        }
+        self.source[self.current..].chars().next().unwrap_or('\0')
     }
 
     fn peek_next(&self) -> char {
-        match self.current + 1 >= self.source.len() {
-            true => '\0',
-            false => self.source.as_bytes()[self.current + 1] as char,
-        } 
+        self.source[self.current..].chars().nth(1).unwrap_or('\0')
     }
 
+    /// Identifiers may start with any Unicode letter or '_'.
     fn is_alpha(&self, c: char) -> bool {
-        (c >= 'a' && c <= 'z') ||
-        (c >= 'A' && c <= 'Z') ||
-        c == '_'
+        c.is_alphabetic() || c == '_'
     }
 
     fn is_alpha_numeric(&self, c: char) -> bool {
-        self.is_alpha(c) || self.is_digit(c)
+        self.is_alpha(c) || c.is_alphanumeric()
     }
 
     fn is_digit(&self, c: char) -> bool {
-        c >= '0' && c <= '9'
+        c.is_ascii_digit()
+    }
+
+    /// Call after consuming a '\n' so columns restart on the next line.
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
+    fn column(&self) -> usize {
+        self.source[self.line_start..self.current].chars().count() + 1
     }
 
     fn is_at_end(&self) -> bool {
@@ -219,9 +459,25 @@ impl<'a> Scanner<'a> {
     }
 
     fn advance(&mut self) -> char {
-        let tmp: usize = self.current;
-        self.current += 1;
-        self.source.as_bytes()[tmp] as char
+        let c: char = self.peek();
+        self.current += c.len_utf8();
+        c
+    }
+
+    fn error(&mut self, code: Code, message: String) {
+        let span: Span = Span::new(self.start_line, self.start_column, self.start, self.current);
+        self.diagnostics.push(Diagnostic::error(code, message, span));
+    }
+
+    /// Reports an error spanning from `start` (on the current line) to `current`.
+    fn error_at(&mut self, start: usize, code: Code, message: String) {
+        let span: Span = self.span_from(start);
+        self.diagnostics.push(Diagnostic::error(code, message, span));
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        let column: usize = self.source[self.line_start..start].chars().count() + 1;
+        Span::new(self.line, column, start, self.current)
     }
 
     fn add_token_helper(&mut self, token_type: TokenType) {
@@ -230,6 +486,236 @@ impl<'a> Scanner<'a> {
 
     fn add_token(&mut self, token_type: TokenType, literal: Literal) {
         let text: &str = &self.source[self.start..self.current];
-        self.tokens.push(Token::from(token_type, String::from(text), literal, self.line));
+        let mut token: Token = Token::from(token_type, String::from(text), literal, self.start_line)
+            .with_position(self.start_column, self.column(), self.start, self.current);
+        token.leading_trivia = std::mem::take(&mut self.trivia);
+        self.tokens.push(token);
+    }
+
+    fn add_token_at_end(&mut self, column: usize, end: usize) {
+        let mut token: Token = Token::from(TokenType::Eof, String::new(), Literal::Empty, self.line)
+            .with_position(column, column, end, end);
+        token.leading_trivia = std::mem::take(&mut self.trivia);
+        self.tokens.push(token);
+    }
+
+    /// Records the text just scanned, which produced no token, as trivia. It
+    /// trails the previous token until the first newline, and leads the next
+    /// token after that.
+    fn add_trivia(&mut self) {
+        let text: &str = &self.source[self.start..self.current];
+        let kind: TriviaKind = match text.chars().next() {
+            Some('\n') => TriviaKind::Newline,
+            Some(' ' | '\r' | '\t') => TriviaKind::Whitespace,
+            Some('/') if text.starts_with("//") => TriviaKind::LineComment,
+            Some('/') if text.starts_with("/*") => TriviaKind::BlockComment,
+            _ => TriviaKind::Skipped,
+        };
+
+        let trails: bool = self.trivia.is_empty() && kind != TriviaKind::Newline && !text.contains('\n');
+        let pieces: &mut Vec<Trivia> = match (trails, self.tokens.last_mut()) {
+            (true, Some(token)) => &mut token.trailing_trivia,
+            _ => &mut self.trivia,
+        };
+
+        // Whitespace is scanned a character at a time; keep each run together.
+        match pieces.last_mut() {
+            Some(last) if last.kind == kind && kind != TriviaKind::Newline => last.text.push_str(text),
+            _ => pieces.push(Trivia { kind, text: String::from(text) }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::token::{Token, Trivia, TriviaKind};
+    use crate::token_type::TokenType;
+    use crate::scanner::Scanner;
+    use crate::diagnostic::Code;
+
+    fn scan(source: &str) -> Vec<Token> {
+        let mut scanner: Scanner = Scanner::new(String::from(source));
+        let tokens: Vec<Token> = scanner.scan_tokens();
+        assert!(scanner.diagnostics().is_empty(), "{:?}", scanner.diagnostics());
+        tokens
+    }
+
+    #[test]
+    fn test_token_positions() {
+        let tokens: Vec<Token> = scan("var answer = 42;");
+        let answer: &Token = &tokens[1];
+
+        assert_eq!((answer.line, answer.column, answer.end_column), (1, 5, 11));
+        assert_eq!((answer.start, answer.end), (4, 10));
+        assert_eq!(tokens[3].column, 14);
+    }
+
+    #[test]
+    fn test_positions_after_multi_line_string_and_block_comment() {
+        let source: &str = "print \"one\ntwo\";\n/* a\n  b */ x;";
+        let tokens: Vec<Token> = scan(source);
+
+        let string: &Token = &tokens[1];
+        assert_eq!(string.token_type, TokenType::String);
+        assert_eq!((string.line, string.column, string.end_column), (1, 7, 5));
+        assert_eq!(&source[string.start..string.end], "\"one\ntwo\"");
+
+        let x: &Token = &tokens[3];
+        assert_eq!((x.line, x.column, x.end_column), (4, 8, 9));
+        assert_eq!(&source[x.start..x.end], "x");
+
+        let eof: &Token = tokens.last().unwrap();
+        assert_eq!((eof.line, eof.column, eof.start), (4, 10, source.len()));
+    }
+
+    #[test]
+    fn test_unicode_strings_and_comments_round_trip() {
+        let source: &str = "// コメント\nprint \"héllo, 世界 👋\"; /* ñ */ print 1;";
+        let tokens: Vec<Token> = scan(source);
+
+        assert_eq!(tokens[1].literal.to_string(), "héllo, 世界 👋");
+        assert_eq!(&source[tokens[1].start..tokens[1].end], "\"héllo, 世界 👋\"");
+        assert_eq!((tokens[2].line, tokens[2].column), (2, 20));
+        assert_eq!(tokens[3].column, 30);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let tokens: Vec<Token> = scan("var größe = 1; var 名前 = größe;");
+        let identifiers: Vec<&str> = tokens.iter()
+            .filter(|token| token.token_type == TokenType::Identifier)
+            .map(|token| token.lexeme.as_str())
+            .collect();
+
+        assert_eq!(identifiers, vec!["größe", "名前", "größe"]);
+    }
+
+    #[test]
+    fn test_unexpected_non_ascii_character() {
+        let mut scanner: Scanner = Scanner::new(String::from("print 1 € 2;"));
+        scanner.scan_tokens();
+
+        let span = scanner.diagnostics()[0].span;
+        assert_eq!((span.column, span.start, span.end), (9, 8, 11));
+    }
+
+    #[test]
+    fn test_escape_sequences() {
+        let tokens: Vec<Token> = scan(r#""a\tb\n\"q\" \\ \${x} \u{48}\u{1F600}""#);
+
+        assert_eq!(tokens[0].literal.to_string(), "a\tb\n\"q\" \\ ${x} H😀");
+    }
+
+    #[test]
+    fn test_invalid_escapes_are_reported() {
+        let mut scanner: Scanner = Scanner::new(String::from(r#""\q \u{D800} \u41""#));
+        let tokens: Vec<Token> = scanner.scan_tokens();
+
+        let columns: Vec<usize> = scanner.diagnostics().iter().map(|diagnostic| diagnostic.span.column).collect();
+        assert_eq!(columns, vec![2, 5, 14]);
+        assert!(scanner.diagnostics().iter().all(|diagnostic| diagnostic.code == Code::InvalidEscape));
+        assert_eq!(tokens[0].token_type, TokenType::String);
+    }
+
+    #[test]
+    fn test_positions_after_backslash_newline() {
+        let mut scanner: Scanner = Scanner::new(String::from("print \"\\\nx\";\nprint @;"));
+        let tokens: Vec<Token> = scanner.scan_tokens();
+
+        let positions: Vec<(usize, usize)> = tokens.iter().map(|token| (token.line, token.column)).collect();
+        assert_eq!(positions, vec![(1, 1), (1, 7), (2, 3), (3, 1), (3, 8), (3, 9)]);
+
+        let errors: Vec<(usize, usize)> = scanner.diagnostics().iter().map(|diagnostic| (diagnostic.span.line, diagnostic.span.column)).collect();
+        assert_eq!(errors, vec![(1, 8), (3, 7)]);
+    }
+
+    #[test]
+    fn test_interpolation_tokens() {
+        let tokens: Vec<Token> = scan(r#""a${ {1} }b${c}""#);
+        let types: Vec<TokenType> = tokens.iter().map(|token| token.token_type.clone()).collect();
+
+        assert_eq!(types, vec![
+            TokenType::Interpolation, TokenType::LeftBrace, TokenType::Number, TokenType::RightBrace,
+            TokenType::Interpolation, TokenType::Identifier, TokenType::String, TokenType::Eof,
+        ]);
+        assert_eq!(tokens[4].lexeme, "}b${");
+        assert_eq!(tokens[6].lexeme, "}\"");
+    }
+
+    #[test]
+    fn test_extended_number_literals() {
+        let tokens: Vec<Token> = scan("0x1F 0XfF 0b1010 1e-9 2.5E3 1_000_000 0x_FF_FF 3.141_5 7e+2");
+        let values: Vec<String> = tokens.iter()
+            .filter(|token| token.token_type == TokenType::Number)
+            .map(|token| token.literal.to_string())
+            .collect();
+
+        assert_eq!(values, vec!["31", "255", "10", "0.000000001", "2500", "1000000", "65535", "3.1415", "700"]);
+        assert_eq!(tokens[5].lexeme, "1_000_000");
+    }
+
+    #[test]
+    fn test_malformed_number_literals() {
+        for source in ["0x", "0b", "1e", "2.5E+", "0b102", "0xFG", "1_", "1__0", "0x1_"] {
+            let mut scanner: Scanner = Scanner::new(String::from(source));
+            let tokens: Vec<Token> = scanner.scan_tokens();
+
+            assert_eq!(scanner.diagnostics().len(), 1, "{}", source);
+            assert_eq!(scanner.diagnostics()[0].code, Code::InvalidNumber, "{}", source);
+            assert_eq!(tokens.len(), 1, "{} should not leave stray tokens", source);
+        }
+    }
+
+    #[test]
+    fn test_nested_block_comments() {
+        let tokens: Vec<Token> = scan("/* outer /* inner\n */ still\n comment */ a\n/**/ b");
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!((tokens[0].lexeme.as_str(), tokens[0].line, tokens[0].column), ("a", 3, 13));
+        assert_eq!((tokens[1].lexeme.as_str(), tokens[1].line, tokens[1].column), ("b", 4, 6));
+    }
+
+    #[test]
+    fn test_unterminated_block_comment_points_at_opening() {
+        let mut scanner: Scanner = Scanner::new(String::from("print 1;\n  /* a /* b */\nprint 2;"));
+        let tokens: Vec<Token> = scanner.scan_tokens();
+
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[3].line, 3);
+
+        let diagnostic = &scanner.diagnostics()[0];
+        assert_eq!(scanner.diagnostics().len(), 1);
+        assert_eq!(diagnostic.code, Code::UnterminatedComment);
+        assert_eq!((diagnostic.span.line, diagnostic.span.column, diagnostic.span.start, diagnostic.span.end), (2, 3, 11, 13));
+    }
+
+    #[test]
+    fn test_lossless_tokens_reproduce_source() {
+        let sources: [&str; 4] = [
+            "// header\nvar a = 1;  // one\r\n\n\t/* two /* nested */\n */ print a  ;\n",
+            "print \"héllo ${ a + 1 } wörld\";\n  ",
+            "var @ b = 0x; /* never closed\n",
+            include_str!("../examples/counter.lox"),
+        ];
+
+        for source in sources {
+            let mut scanner: Scanner = Scanner::new(String::from(source)).with_trivia(true);
+            let rebuilt: String = scanner.scan_tokens().iter().map(|token| token.full_text()).collect();
+            assert_eq!(rebuilt, source);
+        }
+    }
+
+    #[test]
+    fn test_trivia_attaches_to_neighbouring_tokens() {
+        let mut scanner: Scanner = Scanner::new(String::from("a; // note\n  /* doc */ b")).with_trivia(true);
+        let tokens: Vec<Token> = scanner.scan_tokens();
+
+        let kinds = |trivia: &[Trivia]| trivia.iter().map(|piece| piece.kind).collect::<Vec<TriviaKind>>();
+        assert_eq!(kinds(&tokens[1].trailing_trivia), vec![TriviaKind::Whitespace, TriviaKind::LineComment]);
+        assert_eq!(kinds(&tokens[2].leading_trivia), vec![TriviaKind::Newline, TriviaKind::Whitespace, TriviaKind::BlockComment, TriviaKind::Whitespace]);
+        assert_eq!(tokens[2].leading_trivia[2].text, "/* doc */");
+
+        let plain: Vec<Token> = Scanner::new(String::from("a; // note\n  b")).scan_tokens();
+        assert!(plain.iter().all(|token| token.leading_trivia.is_empty() && token.trailing_trivia.is_empty()));
     }
 }
@@ -0,0 +1,95 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::expr::Expr;
+use crate::error::Error;
+
+#[derive(Debug)]
+pub enum Stmt {
+    Block {
+        statements: Vec<Stmt>,
+    },
+    Class {
+        name: Token,
+        superclass: Option<Expr>,
+        methods: Vec<Stmt>,
+    },
+    Expression {
+        expression: Expr,
+    },
+    Function {
+        name: Token,
+        params: Vec<Token>,
+        body: Rc<Vec<Stmt>>,
+    },
+    If {
+        condition: Expr,
+        then_branch: Box<Stmt>,
+        else_branch: Option<Box<Stmt>>,
+    },
+    Print {
+        expression: Expr,
+    },
+    Return {
+        keyword: Token,
+        value: Option<Expr>,
+    },
+    Var {
+        name: Token,
+        initializer: Option<Expr>,
+    },
+    While {
+        condition: Expr,
+        body: Box<Stmt>,
+    },
+}
+
+fn same_tokens(a: &[Token], b: &[Token]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.equivalent(b))
+}
+
+/// Structural equality, ignoring source positions like `Expr`'s.
+impl PartialEq for Stmt {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Stmt::Block { statements: x }, Stmt::Block { statements: y }) => x == y,
+            (Stmt::Class { name: a, superclass: s1, methods: m1 }, Stmt::Class { name: b, superclass: s2, methods: m2 }) => a.equivalent(b) && s1 == s2 && m1 == m2,
+            (Stmt::Expression { expression: x }, Stmt::Expression { expression: y }) => x == y,
+            (Stmt::Function { name: a, params: p1, body: b1 }, Stmt::Function { name: b, params: p2, body: b2 }) => a.equivalent(b) && same_tokens(p1, p2) && b1 == b2,
+            (Stmt::If { condition: c1, then_branch: t1, else_branch: e1 }, Stmt::If { condition: c2, then_branch: t2, else_branch: e2 }) => c1 == c2 && t1 == t2 && e1 == e2,
+            (Stmt::Print { expression: x }, Stmt::Print { expression: y }) => x == y,
+            (Stmt::Return { value: x, .. }, Stmt::Return { value: y, .. }) => x == y,
+            (Stmt::Var { name: a, initializer: x }, Stmt::Var { name: b, initializer: y }) => a.equivalent(b) && x == y,
+            (Stmt::While { condition: c1, body: b1 }, Stmt::While { condition: c2, body: b2 }) => c1 == c2 && b1 == b2,
+            _ => false,
+        }
+    }
+}
+
+impl Stmt {
+    pub fn accept<R>(&self, visitor: &mut dyn StmtVisitor<R>) -> Result<R, Error> {
+        match self {
+            Stmt::Block { statements } => visitor.visit_block_stmt( statements ),
+            Stmt::Class { name, superclass, methods } => visitor.visit_class_stmt( name, superclass.as_ref(), methods ),
+            Stmt::Expression { expression } => visitor.visit_expression_stmt( expression ),
+            Stmt::Function { name, params, body } => visitor.visit_function_stmt( name, params, body ),
+            Stmt::If { condition, then_branch, else_branch } => visitor.visit_if_stmt( condition, then_branch, else_branch.as_deref() ),
+            Stmt::Print { expression } => visitor.visit_print_stmt( expression ),
+            Stmt::Return { keyword, value } => visitor.visit_return_stmt( keyword, value.as_ref() ),
+            Stmt::Var { name, initializer } => visitor.visit_var_stmt( name, initializer.as_ref() ),
+            Stmt::While { condition, body } => visitor.visit_while_stmt( condition, body ),
+        }
+    }
+}
+
+pub trait StmtVisitor<R> {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<R, Error>;
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<R, Error>;
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<R, Error>;
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<R, Error>;
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<R, Error>;
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<R, Error>;
+    fn visit_return_stmt(&mut self, keyword: &Token, value: Option<&Expr>) -> Result<R, Error>;
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<R, Error>;
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<R, Error>;
+}
@@ -1,12 +1,45 @@
 use crate::token_type::TokenType;
 use crate::literal::Literal;
 
+/// What a piece of trivia is: source text that is not part of any token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// Spaces, tabs and carriage returns.
+    Whitespace,
+    Newline,
+    /// A `//` comment, without its terminating newline.
+    LineComment,
+    /// A `/* */` comment, including any nested comments.
+    BlockComment,
+    /// Text the scanner reported an error for and produced no token from.
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub lexeme: String,
     pub literal: Literal,
+    /// Line the lexeme starts on.
     pub line: usize,
+    /// 1-based column of the first character of the lexeme.
+    pub column: usize,
+    /// 1-based column just past the last character, on the line the lexeme ends on.
+    pub end_column: usize,
+    /// Byte offset of the lexeme in the source.
+    pub start: usize,
+    /// Byte offset just past the lexeme.
+    pub end: usize,
+    /// Trivia since the previous token's trailing trivia. Only filled in lossless mode.
+    pub leading_trivia: Vec<Trivia>,
+    /// Trivia after the lexeme up to, but not including, the next newline. Only filled in lossless mode.
+    pub trailing_trivia: Vec<Trivia>,
 }
 
 impl Token {
@@ -16,10 +49,48 @@ impl Token {
             lexeme,
             literal,
             line,
+            column: 0,
+            end_column: 0,
+            start: 0,
+            end: 0,
+            leading_trivia: vec![],
+            trailing_trivia: vec![],
         }
     }
 
-    pub fn to_string(&self) -> String {
-        format!("{:?}, {}, {:?}", self.token_type, self.lexeme, self.literal)
+    pub fn with_position(mut self, column: usize, end_column: usize, start: usize, end: usize) -> Self {
+        self.column = column;
+        self.end_column = end_column;
+        self.start = start;
+        self.end = end;
+        self
+    }
+
+    /// Whether two tokens are the same kind of token with the same meaning,
+    /// wherever they appear. Literal tokens compare by value, so `0x10` and
+    /// `16` are equivalent.
+    pub fn equivalent(&self, other: &Token) -> bool {
+        if self.token_type != other.token_type { return false; }
+
+        match self.token_type {
+            TokenType::Number | TokenType::String | TokenType::Interpolation => self.literal == other.literal,
+            _ => self.lexeme == other.lexeme,
+        }
+    }
+
+    /// The lexeme surrounded by its trivia. In lossless mode, concatenating
+    /// this for every token reproduces the scanned source exactly.
+    pub fn full_text(&self) -> String {
+        let mut text: String = String::new();
+        for trivia in &self.leading_trivia { text.push_str(&trivia.text); }
+        text.push_str(&self.lexeme);
+        for trivia in &self.trailing_trivia { text.push_str(&trivia.text); }
+        text
+    }
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}, {}, {:?}", self.token_type, self.lexeme, self.literal)
     }
 }
@@ -12,6 +12,8 @@ pub enum TokenType {
 
     // Literals
     Identifier, String, Number,
+    // A string segment that ends in `${`, followed by the embedded expression
+    Interpolation,
 
     // KEYWORDS
     And, Class, Else, False, Fun, For, If, Nil, Or, 
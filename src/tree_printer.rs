@@ -0,0 +1,208 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::error::Error;
+use crate::ast_printer::Printer;
+
+/// Prints one node per line with its children indented beneath it, drawn
+/// like the output of `tree`:
+///
+/// ```text
+/// Binary *
+/// ├── Unary -
+/// │   └── Literal 123
+/// └── Grouping
+///     └── Literal 45.67
+/// ```
+pub struct TreePrinter;
+
+impl TreePrinter {
+    pub fn print(&mut self, expr: &Expr) -> Result<String, Error> {
+        expr.accept(self)
+    }
+
+    fn expressions(&mut self, expressions: Vec<&Expr>) -> Result<Vec<String>, Error> {
+        expressions.into_iter().map(|expr| expr.accept(self)).collect()
+    }
+
+    fn statements(&mut self, statements: &[Stmt]) -> Result<Vec<String>, Error> {
+        statements.iter().map(|statement| statement.accept(self)).collect()
+    }
+}
+
+/// Draws `children` beneath `label`, each already rendered as a subtree.
+fn node(label: String, children: Vec<String>) -> String {
+    let mut result: String = label;
+
+    for (i, child) in children.iter().enumerate() {
+        let last: bool = i == children.len() - 1;
+        for (j, line) in child.lines().enumerate() {
+            let branch: &str = match (j == 0, last) {
+                (true, false) => "├── ",
+                (true, true) => "└── ",
+                (false, false) => "│   ",
+                (false, true) => "    ",
+            };
+            result.push('\n');
+            result.push_str(branch);
+            result.push_str(line);
+        }
+    }
+
+    result
+}
+
+impl Visitor<String> for TreePrinter {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        Ok(node(format!("Assign {}", name.lexeme), self.expressions(vec![value])?))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(node(format!("Binary {}", operator.lexeme), self.expressions(vec![left, right])?))
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        let mut expressions: Vec<&Expr> = vec![callee];
+        expressions.extend(arguments);
+        Ok(node(String::from("Call"), self.expressions(expressions)?))
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<String, Error> {
+        Ok(node(format!("Get .{}", name.lexeme), self.expressions(vec![object])?))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
+        Ok(node(String::from("Grouping"), self.expressions(vec![expression])?))
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let mut children: Vec<String> = vec![format!("Literal {:?}", segments[0])];
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            children.push(expression.accept(self)?);
+            children.push(format!("Literal {:?}", segment));
+        }
+        Ok(node(String::from("Interpolation"), children))
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
+        match literal {
+            Literal::String(value) => Ok(format!("Literal {:?}", value)),
+            Literal::Empty => Ok(String::from("Literal nil")),
+            _ => Ok(format!("Literal {}", literal)),
+        }
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(node(format!("Logical {}", operator.lexeme), self.expressions(vec![left, right])?))
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        Ok(node(format!("Set .{}", name.lexeme), self.expressions(vec![object, value])?))
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(format!("Super .{}", method.lexeme))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<String, Error> {
+        Ok(String::from("This"))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(node(format!("Unary {}", operator.lexeme), self.expressions(vec![right])?))
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(format!("Variable {}", name.lexeme))
+    }
+}
+
+impl StmtVisitor<String> for TreePrinter {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(node(String::from("Block"), self.statements(statements)?))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        let label: String = match superclass {
+            Some(Expr::Variable { name: superclass, .. }) => format!("Class {} < {}", name.lexeme, superclass.lexeme),
+            _ => format!("Class {}", name.lexeme),
+        };
+        Ok(node(label, self.statements(methods)?))
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(node(String::from("Expression"), self.expressions(vec![expression])?))
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        Ok(node(format!("Function {}({})", name.lexeme, params.join(", ")), self.statements(body)?))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let mut children: Vec<String> = vec![condition.accept(self)?, then_branch.accept(self)?];
+        if let Some(else_branch) = else_branch {
+            children.push(else_branch.accept(self)?);
+        }
+        Ok(node(String::from("If"), children))
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(node(String::from("Print"), self.expressions(vec![expression])?))
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        Ok(node(String::from("Return"), self.expressions(value.into_iter().collect())?))
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        Ok(node(format!("Var {}", name.lexeme), self.expressions(initializer.into_iter().collect())?))
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        let children: Vec<String> = vec![condition.accept(self)?, body.accept(self)?];
+        Ok(node(String::from("While"), children))
+    }
+}
+
+impl Printer for TreePrinter {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = String::new();
+        for statement in statements {
+            result.push_str(&statement.accept(self)?);
+            result.push('\n');
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::stmt::Stmt;
+    use crate::ast_printer::Printer;
+    use crate::tree_printer::TreePrinter;
+
+    #[test]
+    fn test_children_are_drawn_beneath_their_parent() {
+        let statements: Vec<Stmt> = Parser::new(Scanner::new(String::from("if (a) print -1 * (2); else b = \"c\";")).scan_tokens()).parse();
+
+        assert_eq!(TreePrinter.print_program(&statements).unwrap(), concat!(
+            "If\n",
+            "├── Variable a\n",
+            "├── Print\n",
+            "│   └── Binary *\n",
+            "│       ├── Unary -\n",
+            "│       │   └── Literal 1\n",
+            "│       └── Grouping\n",
+            "│           └── Literal 2\n",
+            "└── Expression\n",
+            "    └── Assign b\n",
+            "        └── Literal \"c\"\n",
+        ));
+    }
+}
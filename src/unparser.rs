@@ -0,0 +1,626 @@
+use std::rc::Rc;
+
+use crate::token::Token;
+use crate::token_type::TokenType;
+use crate::literal::Literal;
+use crate::expr::{Expr, Visitor};
+use crate::stmt::{Stmt, StmtVisitor};
+use crate::error::Error;
+use crate::ast_printer::Printer;
+
+const INDENT: &str = "    ";
+
+/// Binding strength of each expression form, weakest first, following the
+/// parser's grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Assignment,
+    Or,
+    And,
+    Equality,
+    Comparison,
+    Term,
+    Factor,
+    Unary,
+    Call,
+    Primary,
+}
+
+impl Precedence {
+    fn next(self) -> Precedence {
+        match self {
+            Precedence::Assignment => Precedence::Or,
+            Precedence::Or => Precedence::And,
+            Precedence::And => Precedence::Equality,
+            Precedence::Equality => Precedence::Comparison,
+            Precedence::Comparison => Precedence::Term,
+            Precedence::Term => Precedence::Factor,
+            Precedence::Factor => Precedence::Unary,
+            Precedence::Unary => Precedence::Call,
+            Precedence::Call | Precedence::Primary => Precedence::Primary,
+        }
+    }
+
+    fn of(expr: &Expr) -> Precedence {
+        match expr {
+            Expr::Assign { .. } | Expr::Set { .. } => Precedence::Assignment,
+            Expr::Binary { operator, .. } | Expr::Logical { operator, .. } => Precedence::of_operator(&operator.token_type),
+            Expr::Unary { .. } => Precedence::Unary,
+            Expr::Literal { literal: Literal::Number(n) } if n.is_nan() => Precedence::Factor,
+            // A negative number has to be written with a unary minus.
+            Expr::Literal { literal: Literal::Number(n) } if n.is_sign_negative() => Precedence::Unary,
+            Expr::Call { .. } | Expr::Get { .. } => Precedence::Call,
+            Expr::Grouping { .. } | Expr::Interpolation { .. } | Expr::Literal { .. } | Expr::Super { .. } | Expr::This { .. } | Expr::Variable { .. } => Precedence::Primary,
+        }
+    }
+
+    fn of_operator(token_type: &TokenType) -> Precedence {
+        match token_type {
+            TokenType::Or => Precedence::Or,
+            TokenType::And => Precedence::And,
+            TokenType::EqualEqual | TokenType::BangEqual => Precedence::Equality,
+            TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual => Precedence::Comparison,
+            TokenType::Plus | TokenType::Minus => Precedence::Term,
+            _ => Precedence::Factor,
+        }
+    }
+}
+
+/// Turns an AST back into Lox source, the inverse of the parser.
+///
+/// Parentheses are only added where precedence or associativity requires
+/// them; `Grouping` nodes are always kept. `for` loops come out as the
+/// `while` loops they were desugared into.
+pub struct Unparser {
+    indent: usize,
+}
+
+impl Default for Unparser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Unparser {
+    pub fn new() -> Self {
+        Self {
+            indent: 0,
+        }
+    }
+
+    pub fn unparse(&mut self, statements: &[Stmt]) -> String {
+        let mut result: String = String::new();
+        for statement in statements {
+            result.push_str(&self.statement(statement));
+            result.push('\n');
+        }
+        result
+    }
+
+    pub fn unparse_expr(&mut self, expr: &Expr) -> String {
+        expr.accept(self).unwrap_or_default()
+    }
+
+    fn statement(&mut self, statement: &Stmt) -> String {
+        statement.accept(self).unwrap_or_default()
+    }
+
+    /// Writes `expr`, parenthesised if it binds more loosely than `minimum`.
+    fn operand(&mut self, expr: &Expr, minimum: Precedence) -> String {
+        let source: String = self.unparse_expr(expr);
+        match Precedence::of(expr) < minimum {
+            true => format!("({})", source),
+            false => source,
+        }
+    }
+
+    fn binary(&mut self, left: &Expr, operator: &Token, right: &Expr) -> String {
+        let precedence: Precedence = Precedence::of_operator(&operator.token_type);
+        let left: String = self.operand(left, precedence);
+        let right: String = self.operand(right, precedence.next());
+        format!("{} {} {}", left, operator.lexeme, right)
+    }
+
+    fn block(&mut self, statements: &[Stmt]) -> String {
+        if statements.is_empty() { return String::from("{}"); }
+
+        self.indent += 1;
+        let mut result: String = String::from("{\n");
+        for statement in statements {
+            result.push_str(&INDENT.repeat(self.indent));
+            result.push_str(&self.statement(statement));
+            result.push('\n');
+        }
+        self.indent -= 1;
+        result.push_str(&INDENT.repeat(self.indent));
+        result.push('}');
+        result
+    }
+
+    fn body(&mut self, body: &Stmt) -> String {
+        match body {
+            Stmt::Block { statements } => self.block(statements),
+            _ => self.statement(body),
+        }
+    }
+
+    fn function(&mut self, name: &Token, params: &[Token], body: &[Stmt]) -> String {
+        let params: Vec<&str> = params.iter().map(|param| param.lexeme.as_str()).collect();
+        format!("{}({}) {}", name.lexeme, params.join(", "), self.block(body))
+    }
+}
+
+/// Whether an `else` written after `statement` would attach to an `if` inside it.
+fn dangles(statement: &Stmt) -> bool {
+    match statement {
+        Stmt::If { else_branch: None, .. } => true,
+        Stmt::If { else_branch: Some(else_branch), .. } => dangles(else_branch),
+        Stmt::While { body, .. } => dangles(body),
+        _ => false,
+    }
+}
+
+/// Escapes a string's contents so the scanner reads back the same value.
+fn escape(value: &str) -> String {
+    let mut result: String = String::new();
+    let mut chars = value.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\0' => result.push_str("\\0"),
+            '$' if chars.peek() == Some(&'{') => result.push_str("\\$"),
+            c if c.is_control() => result.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+fn number(value: f64) -> String {
+    match value {
+        // `1e999` overflows back to infinity when scanned.
+        n if n.is_infinite() && n > 0.0 => String::from("1e999"),
+        n if n.is_infinite() => String::from("-1e999"),
+        n if n.is_nan() => String::from("0 / 0"),
+        n => n.to_string(),
+    }
+}
+
+impl Visitor<String> for Unparser {
+    fn visit_assign_expr(&mut self, _id: usize, name: &Token, value: &Expr) -> Result<String, Error> {
+        Ok(format!("{} = {}", name.lexeme, self.operand(value, Precedence::Assignment)))
+    }
+
+    fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(self.binary(left, operator, right))
+    }
+
+    fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &[Expr]) -> Result<String, Error> {
+        let callee: String = self.operand(callee, Precedence::Call);
+        let arguments: Vec<String> = arguments.iter().map(|argument| self.operand(argument, Precedence::Assignment)).collect();
+        Ok(format!("{}({})", callee, arguments.join(", ")))
+    }
+
+    fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Result<String, Error> {
+        Ok(format!("{}.{}", self.operand(object, Precedence::Call), name.lexeme))
+    }
+
+    fn visit_grouping_expr(&mut self, expression: &Expr ) -> Result<String, Error> {
+        Ok(format!("({})", self.unparse_expr(expression)))
+    }
+
+    fn visit_interpolation_expr(&mut self, segments: &[String], expressions: &[Expr]) -> Result<String, Error> {
+        let mut result: String = format!("\"{}", escape(&segments[0]));
+        for (expression, segment) in expressions.iter().zip(&segments[1..]) {
+            result.push_str(&format!("${{{}}}{}", self.unparse_expr(expression), escape(segment)));
+        }
+        result.push('"');
+        Ok(result)
+    }
+
+    fn visit_literal_expr(&mut self, literal: &Literal ) -> Result<String, Error> {
+        match literal {
+            Literal::String(value) => Ok(format!("\"{}\"", escape(value))),
+            Literal::Number(value) => Ok(number(*value)),
+            Literal::Boolean(value) => Ok(value.to_string()),
+            Literal::Empty => Ok(String::from("nil")),
+        }
+    }
+
+    fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(self.binary(left, operator, right))
+    }
+
+    fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Result<String, Error> {
+        let object: String = self.operand(object, Precedence::Call);
+        Ok(format!("{}.{} = {}", object, name.lexeme, self.operand(value, Precedence::Assignment)))
+    }
+
+    fn visit_super_expr(&mut self, _id: usize, _keyword: &Token, method: &Token) -> Result<String, Error> {
+        Ok(format!("super.{}", method.lexeme))
+    }
+
+    fn visit_this_expr(&mut self, _id: usize, _keyword: &Token) -> Result<String, Error> {
+        Ok(String::from("this"))
+    }
+
+    fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Result<String, Error> {
+        Ok(format!("{}{}", operator.lexeme, self.operand(right, Precedence::Unary)))
+    }
+
+    fn visit_variable_expr(&mut self, _id: usize, name: &Token) -> Result<String, Error> {
+        Ok(name.lexeme.clone())
+    }
+}
+
+impl StmtVisitor<String> for Unparser {
+    fn visit_block_stmt(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(self.block(statements))
+    }
+
+    fn visit_class_stmt(&mut self, name: &Token, superclass: Option<&Expr>, methods: &[Stmt]) -> Result<String, Error> {
+        let mut result: String = format!("class {}", name.lexeme);
+        if let Some(superclass) = superclass {
+            result.push_str(&format!(" < {}", self.unparse_expr(superclass)));
+        }
+
+        if methods.is_empty() { return Ok(result + " {}"); }
+
+        self.indent += 1;
+        result.push_str(" {\n");
+        for method in methods {
+            if let Stmt::Function { name, params, body } = method {
+                result.push_str(&INDENT.repeat(self.indent));
+                result.push_str(&self.function(name, params, body));
+                result.push('\n');
+            }
+        }
+        self.indent -= 1;
+        result.push_str(&INDENT.repeat(self.indent));
+        result.push('}');
+        Ok(result)
+    }
+
+    fn visit_expression_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(format!("{};", self.unparse_expr(expression)))
+    }
+
+    fn visit_function_stmt(&mut self, name: &Token, params: &[Token], body: &Rc<Vec<Stmt>>) -> Result<String, Error> {
+        Ok(format!("fun {}", self.function(name, params, body)))
+    }
+
+    fn visit_if_stmt(&mut self, condition: &Expr, then_branch: &Stmt, else_branch: Option<&Stmt>) -> Result<String, Error> {
+        let condition: String = self.unparse_expr(condition);
+        // Braces keep the `else` on this `if`; there is no way to write it without them.
+        let then_branch: String = match else_branch.is_some() && dangles(then_branch) {
+            true => self.block(std::slice::from_ref(then_branch)),
+            false => self.body(then_branch),
+        };
+
+        match else_branch {
+            Some(else_branch) => Ok(format!("if ({}) {} else {}", condition, then_branch, self.body(else_branch))),
+            None => Ok(format!("if ({}) {}", condition, then_branch)),
+        }
+    }
+
+    fn visit_print_stmt(&mut self, expression: &Expr) -> Result<String, Error> {
+        Ok(format!("print {};", self.unparse_expr(expression)))
+    }
+
+    fn visit_return_stmt(&mut self, _keyword: &Token, value: Option<&Expr>) -> Result<String, Error> {
+        match value {
+            Some(value) => Ok(format!("return {};", self.unparse_expr(value))),
+            None => Ok(String::from("return;")),
+        }
+    }
+
+    fn visit_var_stmt(&mut self, name: &Token, initializer: Option<&Expr>) -> Result<String, Error> {
+        match initializer {
+            Some(initializer) => Ok(format!("var {} = {};", name.lexeme, self.unparse_expr(initializer))),
+            None => Ok(format!("var {};", name.lexeme)),
+        }
+    }
+
+    fn visit_while_stmt(&mut self, condition: &Expr, body: &Stmt) -> Result<String, Error> {
+        Ok(format!("while ({}) {}", self.unparse_expr(condition), self.body(body)))
+    }
+}
+
+impl Printer for Unparser {
+    fn print_program(&mut self, statements: &[Stmt]) -> Result<String, Error> {
+        Ok(self.unparse(statements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use crate::token::Token;
+    use crate::token_type::TokenType;
+    use crate::literal::Literal;
+    use crate::expr::Expr;
+    use crate::stmt::Stmt;
+    use crate::scanner::Scanner;
+    use crate::parser::Parser;
+    use crate::unparser::{Unparser, dangles};
+
+    fn token(token_type: TokenType, lexeme: &str) -> Token {
+        Token::from(token_type, String::from(lexeme), Literal::Empty, 1)
+    }
+
+    fn variable(name: &str) -> Expr {
+        Expr::Variable { id: Expr::next_id(), name: token(TokenType::Identifier, name) }
+    }
+
+    fn binary(left: Expr, token_type: TokenType, lexeme: &str, right: Expr) -> Expr {
+        Expr::Binary { left: Box::new(left), operator: token(token_type, lexeme), right: Box::new(right) }
+    }
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let mut scanner: Scanner = Scanner::new(String::from(source));
+        let tokens: Vec<Token> = scanner.scan_tokens();
+        assert!(scanner.diagnostics().is_empty(), "{}\n{:?}", source, scanner.diagnostics());
+
+        let mut parser: Parser = Parser::new(tokens);
+        let statements: Vec<Stmt> = parser.parse();
+        assert!(parser.diagnostics().is_empty(), "{}\n{:?}", source, parser.diagnostics());
+        statements
+    }
+
+    #[test]
+    fn test_minimal_parentheses() {
+        let cases: Vec<(Expr, &str)> = vec![
+            (binary(binary(variable("a"), TokenType::Plus, "+", variable("b")), TokenType::Star, "*", variable("c")), "(a + b) * c"),
+            (binary(variable("a"), TokenType::Plus, "+", binary(variable("b"), TokenType::Star, "*", variable("c"))), "a + b * c"),
+            (binary(binary(variable("a"), TokenType::Minus, "-", variable("b")), TokenType::Minus, "-", variable("c")), "a - b - c"),
+            (binary(variable("a"), TokenType::Minus, "-", binary(variable("b"), TokenType::Minus, "-", variable("c"))), "a - (b - c)"),
+            (Expr::Get { object: Box::new(Expr::Literal { literal: Literal::Number(-1.0) }), name: token(TokenType::Identifier, "x") }, "(-1).x"),
+            (Expr::Assign { id: 0, name: token(TokenType::Identifier, "a"), value: Box::new(Expr::Assign { id: 0, name: token(TokenType::Identifier, "b"), value: Box::new(variable("c")) }) }, "a = b = c"),
+        ];
+
+        for (expr, expected) in cases {
+            assert_eq!(Unparser::new().unparse_expr(&expr), expected);
+        }
+    }
+
+    #[test]
+    fn test_source_round_trips() {
+        let source: &str = concat!(
+            "class B < A {\n",
+            "    init(x, y) {\n",
+            "        this.x = super.m(\"q\\\"\\\\\\${no}${x + 1}\\n${\"${y}\"}\");\n",
+            "    }\n",
+            "}\n",
+            "if (a) {\n",
+            "    if (b) print 1;\n",
+            "} else while (!c or d and e) c = -c;\n",
+            "fun f() {\n",
+            "    return;\n",
+            "}\n",
+        );
+
+        assert_eq!(Unparser::new().unparse(&parse(source)), source);
+    }
+
+    /// A xorshift generator, so the property test needs no extra crates and
+    /// every failure is reproducible from its seed.
+    struct Random(u64);
+
+    impl Random {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+            items[self.below(items.len())].clone()
+        }
+    }
+
+    const NAMES: [&str; 5] = ["a", "b", "foo", "bar_2", "ünï"];
+    const STRINGS: [&str; 6] = ["", "plain", "q\"uote", "back\\slash", "new\nline", "$dollar ${not}"];
+    const NUMBERS: [f64; 10] = [0.0, 1.0, 2.5, 0.1, 123456789.0, 1e21, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY];
+    const OPERATORS: [(TokenType, &str); 10] = [
+        (TokenType::Plus, "+"), (TokenType::Minus, "-"), (TokenType::Star, "*"), (TokenType::Slash, "/"),
+        (TokenType::Less, "<"), (TokenType::LessEqual, "<="), (TokenType::Greater, ">"), (TokenType::GreaterEqual, ">="),
+        (TokenType::EqualEqual, "=="), (TokenType::BangEqual, "!="),
+    ];
+
+    fn name(random: &mut Random) -> Token {
+        token(TokenType::Identifier, random.pick(&NAMES))
+    }
+
+    /// An operand as a tree built in code might have it: with no `Grouping`
+    /// where precedence needs parentheses, which leaves them to the
+    /// `Unparser`, and with the odd `Grouping` where it doesn't.
+    fn operand(random: &mut Random, depth: usize) -> Expr {
+        let expr: Expr = expression(random, depth);
+        match random.below(10) {
+            0 => Expr::Grouping { expression: Box::new(expr) },
+            _ => expr,
+        }
+    }
+
+    fn expression(random: &mut Random, depth: usize) -> Expr {
+        let choice: usize = match depth {
+            0 => random.below(5),
+            _ => random.below(14),
+        };
+        let depth: usize = depth.saturating_sub(1);
+
+        match choice {
+            0 => Expr::Literal { literal: Literal::Number(random.pick(&NUMBERS)) },
+            1 => Expr::Literal { literal: Literal::String(String::from(random.pick(&STRINGS))) },
+            2 => Expr::Literal { literal: random.pick(&[Literal::Boolean(true), Literal::Boolean(false), Literal::Empty]) },
+            3 => variable(random.pick(&NAMES)),
+            4 => match random.below(2) {
+                0 => Expr::This { id: Expr::next_id(), keyword: token(TokenType::This, "this") },
+                _ => Expr::Super { id: Expr::next_id(), keyword: token(TokenType::Super, "super"), method: name(random) },
+            },
+            5 | 6 => {
+                let (token_type, lexeme) = random.pick(&OPERATORS);
+                let left: Expr = operand(random, depth);
+                binary(left, token_type, lexeme, operand(random, depth))
+            },
+            7 => {
+                let (token_type, lexeme) = random.pick(&[(TokenType::And, "and"), (TokenType::Or, "or")]);
+                let left: Expr = operand(random, depth);
+                Expr::Logical { left: Box::new(left), operator: token(token_type, lexeme), right: Box::new(operand(random, depth)) }
+            },
+            8 => {
+                let (token_type, lexeme) = random.pick(&[(TokenType::Minus, "-"), (TokenType::Bang, "!")]);
+                Expr::Unary { operator: token(token_type, lexeme), right: Box::new(operand(random, depth)) }
+            },
+            9 => {
+                let callee: Expr = operand(random, depth);
+                let arguments: Vec<Expr> = (0..random.below(3)).map(|_| operand(random, depth)).collect();
+                Expr::Call { callee: Box::new(callee), paren: token(TokenType::RightParen, ")"), arguments }
+            },
+            10 => Expr::Get { object: Box::new(operand(random, depth)), name: name(random) },
+            11 => Expr::Assign { id: Expr::next_id(), name: name(random), value: Box::new(operand(random, depth)) },
+            12 => {
+                let object: Expr = operand(random, depth);
+                Expr::Set { object: Box::new(object), name: name(random), value: Box::new(operand(random, depth)) }
+            },
+            _ => interpolation(random, depth),
+        }
+    }
+
+    fn interpolation(random: &mut Random, depth: usize) -> Expr {
+        let expressions: Vec<Expr> = (0..1 + random.below(2)).map(|_| expression(random, depth)).collect();
+        let segments: Vec<String> = (0..=expressions.len()).map(|_| String::from(random.pick(&STRINGS))).collect();
+        Expr::Interpolation { segments, expressions }
+    }
+
+    fn statement(random: &mut Random, depth: usize) -> Stmt {
+        let choice: usize = match depth {
+            0 => random.below(4),
+            _ => random.below(9),
+        };
+        let depth: usize = depth.saturating_sub(1);
+        let optional = |random: &mut Random| match random.below(2) {
+            0 => None,
+            _ => Some(expression(random, depth)),
+        };
+
+        match choice {
+            0 => Stmt::Print { expression: expression(random, depth) },
+            1 => Stmt::Expression { expression: expression(random, depth) },
+            2 => Stmt::Var { name: name(random), initializer: optional(random) },
+            3 => Stmt::Return { keyword: token(TokenType::Return, "return"), value: optional(random) },
+            4 => Stmt::Block { statements: (0..random.below(3)).map(|_| statement(random, depth)).collect() },
+            5 => {
+                let condition: Expr = expression(random, depth);
+                let mut then_branch: Stmt = branch(random, depth);
+                let else_branch: Option<Box<Stmt>> = match random.below(2) {
+                    0 => None,
+                    _ => Some(Box::new(branch(random, depth))),
+                };
+                // The parser attaches an `else` to the nearest `if`, so it never builds this shape bare.
+                if else_branch.is_some() && dangles(&then_branch) {
+                    then_branch = Stmt::Block { statements: vec![then_branch] };
+                }
+                Stmt::If { condition, then_branch: Box::new(then_branch), else_branch }
+            },
+            6 => Stmt::While { condition: expression(random, depth), body: Box::new(branch(random, depth)) },
+            7 => function(random, depth),
+            _ => Stmt::Class {
+                name: name(random),
+                superclass: match random.below(2) {
+                    0 => None,
+                    _ => Some(variable(random.pick(&NAMES))),
+                },
+                methods: (0..random.below(3)).map(|_| function(random, depth)).collect(),
+            },
+        }
+    }
+
+    /// A statement that may stand alone as a branch or loop body, which rules out declarations.
+    fn branch(random: &mut Random, depth: usize) -> Stmt {
+        loop {
+            match statement(random, depth) {
+                Stmt::Var { .. } | Stmt::Function { .. } | Stmt::Class { .. } => continue,
+                statement => return statement,
+            }
+        }
+    }
+
+    fn function(random: &mut Random, depth: usize) -> Stmt {
+        let params: Vec<Token> = (0..random.below(3)).map(|_| name(random)).collect();
+        let body: Vec<Stmt> = (0..random.below(3)).map(|_| statement(random, depth)).collect();
+        Stmt::Function { name: name(random), params, body: Rc::new(body) }
+    }
+
+    /// `expr` the way the parser reads it back: without `Grouping`s, and with
+    /// the numbers that have no literal syntax in the form `number` writes.
+    fn canonical(expr: Expr) -> Expr {
+        let boxed = |expr: Box<Expr>| Box::new(canonical(*expr));
+
+        match expr {
+            Expr::Grouping { expression } => canonical(*expression),
+            Expr::Literal { literal: Literal::Number(n) } if n.is_nan() => {
+                let zero = || Expr::Literal { literal: Literal::Number(0.0) };
+                binary(zero(), TokenType::Slash, "/", zero())
+            },
+            Expr::Literal { literal: Literal::Number(n) } if n.is_sign_negative() => Expr::Unary {
+                operator: token(TokenType::Minus, "-"),
+                right: Box::new(Expr::Literal { literal: Literal::Number(-n) }),
+            },
+            Expr::Assign { id, name, value } => Expr::Assign { id, name, value: boxed(value) },
+            Expr::Binary { left, operator, right } => Expr::Binary { left: boxed(left), operator, right: boxed(right) },
+            Expr::Call { callee, paren, arguments } => Expr::Call { callee: boxed(callee), paren, arguments: arguments.into_iter().map(canonical).collect() },
+            Expr::Get { object, name } => Expr::Get { object: boxed(object), name },
+            Expr::Interpolation { segments, expressions } => Expr::Interpolation { segments, expressions: expressions.into_iter().map(canonical).collect() },
+            Expr::Logical { left, operator, right } => Expr::Logical { left: boxed(left), operator, right: boxed(right) },
+            Expr::Set { object, name, value } => Expr::Set { object: boxed(object), name, value: boxed(value) },
+            Expr::Unary { operator, right } => Expr::Unary { operator, right: boxed(right) },
+            expr => expr,
+        }
+    }
+
+    fn canonical_statement(statement: Stmt) -> Stmt {
+        let all = |statements: Vec<Stmt>| -> Vec<Stmt> { statements.into_iter().map(canonical_statement).collect() };
+        let boxed = |statement: Box<Stmt>| Box::new(canonical_statement(*statement));
+
+        match statement {
+            Stmt::Block { statements } => Stmt::Block { statements: all(statements) },
+            Stmt::Class { name, superclass, methods } => Stmt::Class { name, superclass: superclass.map(canonical), methods: all(methods) },
+            Stmt::Expression { expression } => Stmt::Expression { expression: canonical(expression) },
+            Stmt::Function { name, params, body } => {
+                let body: Vec<Stmt> = Rc::try_unwrap(body).expect("function body is shared");
+                Stmt::Function { name, params, body: Rc::new(all(body)) }
+            },
+            Stmt::If { condition, then_branch, else_branch } => Stmt::If {
+                condition: canonical(condition),
+                then_branch: boxed(then_branch),
+                else_branch: else_branch.map(boxed),
+            },
+            Stmt::Print { expression } => Stmt::Print { expression: canonical(expression) },
+            Stmt::Return { keyword, value } => Stmt::Return { keyword, value: value.map(canonical) },
+            Stmt::Var { name, initializer } => Stmt::Var { name, initializer: initializer.map(canonical) },
+            Stmt::While { condition, body } => Stmt::While { condition: canonical(condition), body: boxed(body) },
+        }
+    }
+
+    #[test]
+    fn test_parse_of_unparse_is_identity() {
+        for seed in 1..=500u64 {
+            let mut random: Random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+            let statements: Vec<Stmt> = (0..1 + random.below(4)).map(|_| statement(&mut random, 4)).collect();
+
+            let source: String = Unparser::new().unparse(&statements);
+            let parsed: Vec<Stmt> = parse(&source).into_iter().map(canonical_statement).collect();
+            let expected: Vec<Stmt> = statements.into_iter().map(canonical_statement).collect();
+            assert!(parsed == expected, "seed {} produced:\n{}", seed, source);
+        }
+    }
+}
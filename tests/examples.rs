@@ -0,0 +1,348 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// A script written to the temporary directory, removed again when dropped so
+/// that a failing assertion doesn't leave it behind.
+struct TempScript {
+    path: PathBuf,
+}
+
+impl TempScript {
+    fn new(name: &str, source: &str) -> Self {
+        let path: PathBuf = std::env::temp_dir().join(format!("lox-rs-{}-{}.lox", std::process::id(), name));
+        std::fs::write(&path, source).expect("failed to write temporary script");
+        Self { path }
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempScript {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn run_file(path: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lox-rs"))
+        .arg(path)
+        .output()
+        .expect("failed to run the lox-rs binary")
+}
+
+fn run_example(name: &str) -> Output {
+    run_file(&format!("{}/examples/{}", env!("CARGO_MANIFEST_DIR"), name))
+}
+
+fn run_source(name: &str, source: &str) -> Output {
+    let script: TempScript = TempScript::new(name, source);
+    run_file(script.path().to_str().unwrap())
+}
+
+fn run_command(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lox-rs"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the lox-rs binary");
+
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).expect("failed to write to stdin");
+    child.wait_with_output().expect("failed to wait for the lox-rs binary")
+}
+
+#[test]
+fn test_counter_closure() {
+    let output: Output = run_example("counter.lox");
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n2\n");
+}
+
+#[test]
+fn test_closure_binds_to_declaration_in_scope() {
+    let output: Output = run_source("closure_binding", r#"
+        var a = "global";
+        {
+            fun showA() { print a; }
+            showA();
+            var a = "block";
+            showA();
+        }
+    "#);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "global\nglobal\n");
+}
+
+#[test]
+fn test_static_errors_exit_before_running() {
+    let sources: [(&str, &str); 3] = [
+        ("own_initializer", "print \"ran\"; { var a = a; }"),
+        ("top_level_return", "print \"ran\"; return 1;"),
+        ("redeclared_local", "print \"ran\"; { var a = 1; var a = 2; }"),
+    ];
+
+    for (name, source) in sources {
+        let output: Output = run_source(name, source);
+
+        assert_eq!(output.status.code(), Some(65), "{}", name);
+        assert!(output.stdout.is_empty(), "{} printed {:?}", name, String::from_utf8_lossy(&output.stdout));
+    }
+}
+
+#[test]
+fn test_classes_with_initializers_and_super() {
+    let output: Output = run_source("classes", r#"
+        class Shape {
+            init(name) { this.name = name; }
+            describe() { return this.name + " with area " + this.area(); }
+            area() { return "unknown"; }
+        }
+        class Square < Shape {
+            init(side) { super.init("square"); this.side = side; }
+            area() { return "9"; }
+        }
+        var square = Square(3);
+        print square.describe();
+        print square.init(4) == square;
+        print square.side;
+        print square;
+    "#);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "square with area 9\ntrue\n4\nSquare instance\n");
+}
+
+#[test]
+fn test_string_interpolation() {
+    let output: Output = run_source("interpolation", r#"
+        var name = "Lox";
+        var items = 3;
+        print "Hello ${name}! You have ${items * 2} items${"${"!"}"}";
+        print "\u{2603} \${not interpolated}";
+    "#);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Hello Lox! You have 6 items!\n☃ ${not interpolated}\n");
+}
+
+#[test]
+fn test_interpolation_ignores_a_user_defined_str() {
+    let output: Output = run_source("shadowed_str", r#"
+        { var str = 1; print "a${2}b"; }
+        { var str = "c${3}"; print str; }
+        fun str(x) { return "hijack"; }
+        print "v=${1}";
+    "#);
+
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a2b\nc3\nv=1\n");
+}
+
+#[test]
+fn test_fmt_check_then_rewrite() {
+    let script: TempScript = TempScript::new("fmt", "var a=1;// one\nprint a;\n");
+
+    let fmt = |check: bool| -> Output {
+        let mut command: Command = Command::new(env!("CARGO_BIN_EXE_lox-rs"));
+        command.arg("fmt");
+        if check { command.arg("--check"); }
+        command.arg(script.path()).output().expect("failed to run the lox-rs binary")
+    };
+
+    let output: Output = fmt(true);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Would reformat"));
+
+    assert!(fmt(false).status.success());
+    assert_eq!(std::fs::read_to_string(script.path()).unwrap(), "var a = 1; // one\nprint a;\n");
+    assert!(fmt(true).status.success());
+}
+
+#[test]
+fn test_fmt_reads_stdin() {
+    let output: Output = run_command(&["fmt", "-"], "var a=1;// one\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "var a = 1; // one\n");
+
+    let output: Output = run_command(&["fmt", "--check", "-"], "var a=1;\n");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Would reformat: <stdin>"));
+}
+
+#[test]
+fn test_ast_formats_are_selectable() {
+    let script: TempScript = TempScript::new("ast", "print 1 + 2;\n");
+
+    let ast = |format: &str| -> Output {
+        Command::new(env!("CARGO_BIN_EXE_lox-rs"))
+            .args(["ast", &format!("--format={}", format)])
+            .arg(script.path())
+            .output()
+            .expect("failed to run the lox-rs binary")
+    };
+
+    assert_eq!(String::from_utf8_lossy(&ast("sexpr").stdout), "(print (+ 1 2))\n");
+    assert_eq!(String::from_utf8_lossy(&ast("rpn").stdout), "1 2 + print\n");
+    assert!(String::from_utf8_lossy(&ast("tree").stdout).starts_with("Print\n└── Binary +\n"));
+    assert!(String::from_utf8_lossy(&ast("dot").stdout).starts_with("digraph ast {"));
+    assert!(String::from_utf8_lossy(&ast("json").stdout).starts_with("{\"version\":1,"));
+    assert_eq!(ast("yaml").status.code(), Some(64));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_ast_write_failure_exits_with_ioerr() {
+    let script: TempScript = TempScript::new("ast_full", "print 1;\n");
+    let full: std::fs::File = std::fs::File::create("/dev/full").expect("failed to open /dev/full");
+
+    let output: Output = Command::new(env!("CARGO_BIN_EXE_lox-rs"))
+        .args(["ast", "--format=json"])
+        .arg(script.path())
+        .stdout(full)
+        .output()
+        .expect("failed to run the lox-rs binary");
+
+    assert_eq!(output.status.code(), Some(74));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("rlox: can't write 'standard output': "));
+}
+
+#[test]
+fn test_help_and_version() {
+    let output: Output = run_command(&["--help"], "");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Usage: rlox"));
+
+    let output: Output = run_command(&["--version"], "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), format!("rlox {}\n", env!("CARGO_PKG_VERSION")));
+
+    assert_eq!(run_command(&["--frobnicate"], "").status.code(), Some(64));
+    assert_eq!(run_command(&["tokens"], "").status.code(), Some(64));
+}
+
+#[test]
+fn test_eval_and_stdin_receive_arguments() {
+    let output: Output = run_command(&["-e", "print argc(); print arg(0) + arg(1);", "a", "b"], "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\nab\n");
+
+    let output: Output = run_command(&["-e", "1 + 2"], "");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+
+    let output: Output = run_command(&["eval", "arg(0)", "a"], "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a\n");
+
+    let output: Output = run_command(&["run", "-", "x"], "print arg(0); print arg(1);");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "x\nnil\n");
+}
+
+#[test]
+fn test_tokens_and_check() {
+    let output: Output = run_command(&["tokens", "-"], "var a;");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1:1 Var, var, Empty\n1:5 Identifier, a, Empty\n1:6 Semicolon, ;, Empty\n1:7 Eof, , Empty\n");
+
+    let output: Output = run_command(&["check", "-"], "print \"not run\";");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+
+    let output: Output = run_command(&["check", "-"], "{ var a = a; }");
+    assert_eq!(output.status.code(), Some(65));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("own initializer"));
+}
+
+#[test]
+fn test_exit_codes_follow_sysexits() {
+    let output: Output = run_command(&["-e", "print 1; print -\"a\"; print 2;"], "");
+    assert_eq!(output.status.code(), Some(70));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n");
+
+    let output: Output = run_file("no/such/script.lox");
+    assert_eq!(output.status.code(), Some(66));
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("rlox: can't open 'no/such/script.lox': "));
+
+    let output: Output = run_command(&["fmt", "no/such/script.lox"], "");
+    assert_eq!(output.status.code(), Some(66));
+
+    let output: Output = run_file(env!("CARGO_MANIFEST_DIR"));
+    assert_eq!(output.status.code(), Some(74));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+}
+
+#[test]
+fn test_repl_keeps_state_after_errors() {
+    let output: Output = run_command(&["repl"], "var a = 1;\nprint a +;\na + 1\nb;\nfun f() { return a; }\nf();\na = \"x\"\n");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\nx\n");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Expected expression."), "{}", stderr);
+    assert!(stderr.contains("Undefined variable 'b'."), "{}", stderr);
+}
+
+#[test]
+fn test_repl_entries_span_lines() {
+    let source: &str = "fun add(a,\n        b) {\n\n    return a + b;\n}\nprint \"a\nb\";\nadd(1,\n2)\nvar c =\n";
+    let output: Output = run_command(&["repl"], source);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a\nb\n3\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Expected expression."));
+}
+
+#[test]
+fn test_repl_commands() {
+    let script: TempScript = TempScript::new("load", "var loaded = \"yes\";\n");
+
+    let source: String = format!(
+        ":tokens a;\n:ast 1 + 2 * 3\n:load {}\nvar n = 1;\n:env\n:reset\n:env\n:time n\n:time 1\n:frobnicate\n:help\n",
+        script.path().display(),
+    );
+    let output: Output = run_command(&["repl"], &source);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with(concat!(
+        "1:1 Identifier, a, Empty\n1:2 Semicolon, ;, Empty\n1:3 Eof, , Empty\n",
+        "(; (+ 1 (* 2 3)))\n",
+        "clock = <native fn>\nloaded = \"yes\"\nn = 1\n",
+        "clock = <native fn>\n",
+        "Took ",
+    )), "{}", stdout);
+    assert!(stdout.contains("\n1\nTook "), "{}", stdout);
+    assert!(stdout.contains(":tokens <code>"), "{}", stdout);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Undefined variable 'n'."), "{}", stderr);
+    assert!(stderr.contains("Unknown command ':frobnicate'."), "{}", stderr);
+}
+
+#[test]
+fn test_unbounded_recursion_is_a_runtime_error() {
+    let output: Output = run_source("recursion", "fun f() { f(); }\nf();\n");
+
+    assert_eq!(output.status.code(), Some(70));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Stack overflow."));
+}
+
+#[test]
+fn test_deep_nesting_is_a_syntax_error() {
+    let parentheses: String = format!("print {}1{};", "(".repeat(5000), ")".repeat(5000));
+    let blocks: String = format!("{}{}", "{".repeat(300), "}".repeat(300));
+
+    for source in [parentheses, blocks] {
+        let output: Output = run_command(&["check", "-"], &source);
+
+        assert_eq!(output.status.code(), Some(65));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(stderr.matches("error").count(), 1, "{}", stderr);
+        assert!(stderr.contains("error[E0104]: Too much nesting."), "{}", stderr);
+    }
+
+    let output: Output = run_command(&["-e", &format!("print {}1;", "-".repeat(200))], "");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n");
+}